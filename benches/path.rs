@@ -0,0 +1,204 @@
+//! Benchmarks of mesh preprocessing and path queries, split by phase: `preprocess` builds the
+//! mesh, `dijkstra` stops queries before shortening, `shorten` runs them to convergence and
+//! `cut` also cuts the mesh along the path. `repeated` compares queries on a persistent `Mesh`
+//! with queries that preprocess the mesh every time.
+//!
+//! Meshes are a procedural terrain and sphere, and the demo bunny decoded from
+//! `www/public/bunny.drc` into `benches/bunny.obj`. Set `BENCH_MESH` to the path of another OBJ
+//! file to add it.
+
+#[path = "../tests/common/mod.rs"]
+mod common;
+
+use common::*;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use geodesic_path::{compute_path_with_options, get_path_mesh, Mesh, PathOptions};
+
+///Named mesh with query pairs of growing length.
+struct Case {
+    name: String,
+    mesh: common::Mesh,
+    queries: Vec<(&'static str, usize, usize)>,
+}
+
+fn cases() -> Vec<Case> {
+    let mut cases = vec![];
+    let n = 100;
+    let mut terrain = plane(n, 1.0);
+    for (i, z) in terrain
+        .coordinates
+        .iter_mut()
+        .skip(2)
+        .step_by(3)
+        .enumerate()
+    {
+        let (x, y) = ((i % (n + 1)) as f32 / 10.0, (i / (n + 1)) as f32 / 10.0);
+        *z = 0.05 * (x.sin() + y.cos());
+    }
+    let at = |x: usize, y: usize| y * (n + 1) + x;
+    cases.push(Case {
+        name: format!("terrain {}x{}", n, n),
+        mesh: terrain,
+        queries: vec![
+            ("short", at(10, 10), at(20, 15)),
+            ("medium", at(10, 10), at(60, 40)),
+            ("long", at(0, 0), at(n, n)),
+        ],
+    });
+
+    let (around, along) = (128, 64);
+    let vertex = |row: usize, column: usize| 1 + (row - 1) * around + column;
+    cases.push(Case {
+        name: format!("sphere {}x{}", around, along),
+        mesh: sphere(around, along),
+        queries: vec![
+            ("short", vertex(32, 0), vertex(36, 8)),
+            ("medium", vertex(16, 0), vertex(40, 40)),
+            ("long", 0, vertex(along - 1, around / 3)),
+        ],
+    });
+
+    cases.push(obj_case(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/benches/bunny.obj"
+    )));
+    if let Ok(path) = std::env::var("BENCH_MESH") {
+        cases.push(obj_case(&path));
+    }
+    cases
+}
+
+///Case of an OBJ file, with queries from vertex 0 to the vertices at growing fractions of the
+///largest distance from it.
+fn obj_case(path: &str) -> Case {
+    let mesh = read_obj(path);
+    let count = mesh.coordinates.len() / 3;
+    let from_start = |vertex: usize| distance(mesh.vertex(0), mesh.vertex(vertex));
+    let farthest = (0..count).map(from_start).fold(0.0, f32::max);
+    let queries = [("short", 0.05), ("medium", 0.3), ("long", 1.0)]
+        .iter()
+        .map(|&(length, fraction)| {
+            let gap = |vertex: usize| (from_start(vertex) - fraction * farthest).abs();
+            let end = (0..count)
+                .min_by(|&a, &b| gap(a).partial_cmp(&gap(b)).unwrap())
+                .unwrap();
+            (length, 0, end)
+        })
+        .collect();
+    let name = std::path::Path::new(path).file_stem().unwrap();
+    Case {
+        name: name.to_string_lossy().into_owned(),
+        mesh,
+        queries,
+    }
+}
+
+///Reads vertices and triangles of an OBJ file, fanning polygons.
+fn read_obj(path: &str) -> common::Mesh {
+    let text = std::fs::read_to_string(path).unwrap_or_else(|_| panic!("{} is not readable", path));
+    let (mut coordinates, mut indices) = (vec![], vec![]);
+    for line in text.lines() {
+        let mut words = line.split_whitespace();
+        match words.next() {
+            Some("v") => coordinates.extend(words.take(3).map(|x| x.parse::<f32>().unwrap())),
+            Some("f") => {
+                let face: Vec<usize> = words
+                    .map(|x| x.split('/').next().unwrap().parse::<usize>().unwrap() - 1)
+                    .collect();
+                for k in 1..face.len() - 1 {
+                    indices.extend_from_slice(&[face[0], face[k], face[k + 1]]);
+                }
+            }
+            _ => {}
+        }
+    }
+    common::Mesh {
+        coordinates,
+        indices,
+    }
+}
+
+fn options(max_iterations: usize) -> PathOptions {
+    let mut options = PathOptions::new();
+    options.set_max_iterations(max_iterations);
+    options
+}
+
+fn preprocess(c: &mut Criterion) {
+    let mut group = c.benchmark_group("preprocess");
+    for case in cases().iter() {
+        group.bench_function(&case.name, |b| {
+            b.iter(|| Mesh::new(case.mesh.coordinates.clone(), case.mesh.indices.clone()))
+        });
+    }
+    group.finish();
+}
+
+fn queries(c: &mut Criterion) {
+    for (phase, max_iterations) in [("dijkstra", 0), ("shorten", usize::MAX)].iter() {
+        let mut group = c.benchmark_group(*phase);
+        for case in cases().iter() {
+            let mesh = Mesh::new(case.mesh.coordinates.clone(), case.mesh.indices.clone());
+            for &(length, start, end) in case.queries.iter() {
+                let id = BenchmarkId::new(&case.name, length);
+                group.bench_function(id, |b| {
+                    b.iter(|| mesh.compute_path(start, end, &options(*max_iterations)))
+                });
+            }
+        }
+        group.finish();
+    }
+}
+
+fn cut(c: &mut Criterion) {
+    let mut group = c.benchmark_group("cut");
+    group.sample_size(10);
+    for case in cases().iter() {
+        for &(length, start, end) in case.queries.iter() {
+            group.bench_function(BenchmarkId::new(&case.name, length), |b| {
+                b.iter(|| {
+                    get_path_mesh(
+                        start,
+                        end,
+                        case.mesh.coordinates.clone(),
+                        case.mesh.indices.clone(),
+                    )
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+fn repeated(c: &mut Criterion) {
+    let mut group = c.benchmark_group("repeated");
+    group.sample_size(10);
+    for case in cases().iter() {
+        let queries = &case.queries;
+        group.bench_function(BenchmarkId::new(&case.name, "persistent"), |b| {
+            b.iter(|| {
+                let mesh = Mesh::new(case.mesh.coordinates.clone(), case.mesh.indices.clone());
+                for &(_, start, end) in queries.iter() {
+                    mesh.compute_path(start, end, &options(usize::MAX));
+                }
+            })
+        });
+        group.bench_function(BenchmarkId::new(&case.name, "rebuilt"), |b| {
+            b.iter(|| {
+                for &(_, start, end) in queries.iter() {
+                    compute_path_with_options(
+                        start,
+                        end,
+                        &options(usize::MAX),
+                        case.mesh.coordinates.clone(),
+                        case.mesh.indices.clone(),
+                    );
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, preprocess, queries, cut, repeated);
+criterion_main!(benches);
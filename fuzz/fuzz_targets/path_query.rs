@@ -0,0 +1,58 @@
+//! Builds a small mesh from raw bytes and runs path and surface queries on it. Run with
+//! `cargo fuzz run path_query`.
+
+#![no_main]
+
+use geodesic_path::{
+    compute_path_with_options, distance_field, distance_field_from_points, exp_map, geodesic_disc,
+    get_path, get_path_mesh, log_map, trace_geodesic, PathOptions,
+};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if data.len() < 3 {
+        return;
+    }
+    let (header, body) = data.split_at(3);
+    // Up to 32 vertices with coordinates on a 1/16 grid, the rest of the bytes are indices that
+    // may point past the vertices.
+    let count = (header[0] % 32) as usize + 1;
+    let (start, end) = (header[1] as usize % 40, header[2] as usize % 40);
+    let split = body.len().min(count * 3);
+    let coordinates: Vec<f32> = body[..split]
+        .iter()
+        .map(|&x| x as i8 as f32 / 16.0)
+        .collect();
+    let indices: Vec<usize> = body[split..]
+        .iter()
+        .map(|&x| x as usize % (count + 8))
+        .collect();
+
+    let mut options = PathOptions::new();
+    options.set_max_iterations(1000);
+    compute_path_with_options(start, end, &options, coordinates.clone(), indices.clone());
+    get_path(start, end, coordinates.clone(), indices.clone());
+    get_path_mesh(start, end, coordinates.clone(), indices.clone());
+
+    // Surface points on face `end`, with barycentric coordinates and vectors from the header.
+    let vector: Vec<f32> = header.iter().map(|&x| x as i8 as f32 / 16.0).collect();
+    log_map(start, coordinates.clone(), indices.clone());
+    exp_map(start, vector.clone(), coordinates.clone(), indices.clone());
+    trace_geodesic(
+        end,
+        vector.clone(),
+        vector.clone(),
+        4.0,
+        coordinates.clone(),
+        indices.clone(),
+    );
+    geodesic_disc(
+        end,
+        vector.clone(),
+        4.0,
+        coordinates.clone(),
+        indices.clone(),
+    );
+    distance_field(vec![start, end], coordinates.clone(), indices.clone());
+    distance_field_from_points(vec![end], vector, coordinates, indices);
+});
@@ -0,0 +1,41 @@
+use crate::{Data, PathOptions, QueryState};
+
+impl Data {
+    ///Gets the faces and the faces around the vertices blocked by `options`, or nothing if none
+    ///are. Indices out of range are ignored.
+    pub(crate) fn block(&self, options: &PathOptions) -> Vec<bool> {
+        let mut blocked = vec![];
+        let (faces, vertices) = (options.blocked_faces(), options.blocked_vertices());
+        if faces.is_empty() && vertices.is_empty() {
+            return blocked;
+        }
+        blocked.resize(self.faces.len(), false);
+        for face in faces {
+            if let Some(blocked) = blocked.get_mut(face) {
+                *blocked = true;
+            }
+        }
+        for vertex in vertices {
+            for &face in self.vertex_faces.get(vertex).into_iter().flatten() {
+                blocked[face] = true;
+            }
+        }
+        blocked
+    }
+}
+
+impl QueryState {
+    ///Whether the query may not enter some faces.
+    pub(crate) fn has_blocked_faces(&self) -> bool {
+        !self.blocked.is_empty() || self.patch.is_some()
+    }
+
+    ///Whether `face` is blocked or outside the patch of the query.
+    pub(crate) fn is_blocked(&self, face: usize) -> bool {
+        let outside = match self.patch.as_ref() {
+            Some(patch) => !patch.contains(&face),
+            None => false,
+        };
+        outside || self.blocked.get(face).copied().unwrap_or(false)
+    }
+}
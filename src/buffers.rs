@@ -0,0 +1,82 @@
+use crate::{Data, Mesh, PathResult};
+use js_sys::{Float32Array, Float64Array, Uint32Array};
+use wasm_bindgen::prelude::*;
+
+///Index types accepted in index buffers.
+pub trait VertexIndex: Copy {
+    fn index(self) -> usize;
+}
+
+impl VertexIndex for usize {
+    fn index(self) -> usize {
+        self
+    }
+}
+
+impl VertexIndex for u32 {
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+///Vertex and index buffers allocated in wasm memory, for JS to fill in place and build a `Mesh`
+///from without copying them out of JS first.
+#[wasm_bindgen]
+pub struct MeshBuffers {
+    coordinates: Vec<f32>,
+    indices: Vec<u32>,
+}
+
+#[wasm_bindgen]
+impl MeshBuffers {
+    ///Allocates zeroed buffers for `vertex_count` vertices and `face_count` faces.
+    #[wasm_bindgen(constructor)]
+    pub fn new(vertex_count: usize, face_count: usize) -> MeshBuffers {
+        MeshBuffers {
+            coordinates: vec![0.0; 3 * vertex_count],
+            indices: vec![0; 3 * face_count],
+        }
+    }
+
+    ///View of the coordinate buffer. Like every view into wasm memory, it's detached when the
+    ///memory grows, so get it again after any other call into the module.
+    #[wasm_bindgen(getter)]
+    pub fn coordinates(&mut self) -> Float32Array {
+        unsafe { Float32Array::view_mut_raw(self.coordinates.as_mut_ptr(), self.coordinates.len()) }
+    }
+
+    ///View of the index buffer, valid like `coordinates`.
+    #[wasm_bindgen(getter)]
+    pub fn indices(&mut self) -> Uint32Array {
+        unsafe { Uint32Array::view_mut_raw(self.indices.as_mut_ptr(), self.indices.len()) }
+    }
+}
+
+#[wasm_bindgen]
+impl Mesh {
+    ///Builds the mesh from the typed arrays of a geometry, e.g. the position and index attributes
+    ///of a three.js `BufferGeometry`. The arrays are copied into wasm memory, so fill
+    ///`MeshBuffers` instead to skip that copy for large meshes.
+    pub fn from_arrays(coordinates: &[f32], indices: &[u32]) -> Mesh {
+        Mesh::from_data(Data::from_slices(coordinates, indices))
+    }
+
+    ///Builds the mesh from buffers filled in wasm memory.
+    pub fn from_buffers(buffers: &MeshBuffers) -> Mesh {
+        Mesh::from_data(Data::from_slices(&buffers.coordinates, &buffers.indices))
+    }
+}
+
+#[wasm_bindgen]
+impl PathResult {
+    ///View of `positions` without a copy. It's detached when wasm memory grows and invalid once
+    ///the result is freed.
+    pub fn positions_view(&self) -> Float32Array {
+        unsafe { Float32Array::view(self.positions_slice()) }
+    }
+
+    ///View of `lengths`, valid like `positions_view`.
+    pub fn lengths_view(&self) -> Float64Array {
+        unsafe { Float64Array::view(self.lengths_slice()) }
+    }
+}
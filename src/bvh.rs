@@ -0,0 +1,177 @@
+use crate::triangle::Triangle;
+use na::Vector3;
+use nalgebra as na;
+
+const LEAF_SIZE: usize = 4;
+
+///Bounding volume hierarchy over mesh faces, for ray casts and nearest point queries.
+pub(crate) struct Bvh {
+    nodes: Vec<Node>,
+    ///Faces ordered so that every leaf owns a contiguous range.
+    faces: Vec<usize>,
+    ///Face ids of `triangles`.
+    ids: Vec<usize>,
+    triangles: Vec<Triangle>,
+}
+
+struct Node {
+    min: Vector3<f32>,
+    max: Vector3<f32>,
+    ///Range in `faces` for a leaf. An inner node has its first child next to it and the second
+    ///one at `end`.
+    start: usize,
+    end: usize,
+    leaf: bool,
+}
+
+impl Bvh {
+    ///Builds the hierarchy over `faces`, given as face id and triangle. Queries return the ids.
+    pub fn new(faces: Vec<(usize, Triangle)>) -> Bvh {
+        let mut bvh = Bvh {
+            nodes: vec![],
+            faces: (0..faces.len()).collect(),
+            ids: faces.iter().map(|x| x.0).collect(),
+            triangles: faces.into_iter().map(|x| x.1).collect(),
+        };
+        if !bvh.faces.is_empty() {
+            bvh.build(0, bvh.faces.len());
+        }
+        bvh
+    }
+
+    ///Adds the subtree over `faces[start..end]`, splitting at the median centroid along the
+    ///longest axis.
+    fn build(&mut self, start: usize, end: usize) -> usize {
+        let (min, max) = self.bounds(&self.faces[start..end]);
+        let index = self.nodes.len();
+        self.nodes.push(Node {
+            min,
+            max,
+            start,
+            end,
+            leaf: true,
+        });
+        if end - start <= LEAF_SIZE {
+            return index;
+        }
+
+        let extent = max - min;
+        let axis = extent.imax();
+        let triangles = &self.triangles;
+        let middle = (start + end) / 2;
+        self.faces[start..end].select_nth_unstable_by(middle - start, |a, b| {
+            let a = triangles[*a].centroid()[axis];
+            let b = triangles[*b].centroid()[axis];
+            a.partial_cmp(&b).unwrap_or(std::cmp::Ordering::Equal)
+        });
+        self.build(start, middle);
+        let second = self.build(middle, end);
+        self.nodes[index].leaf = false;
+        self.nodes[index].end = second;
+        index
+    }
+
+    fn bounds(&self, faces: &[usize]) -> (Vector3<f32>, Vector3<f32>) {
+        faces.iter().fold(
+            (
+                Vector3::repeat(f32::INFINITY),
+                Vector3::repeat(f32::NEG_INFINITY),
+            ),
+            |(min, max), &face| {
+                let [low, high] = self.triangles[face].aabb();
+                (min.inf(&low), max.sup(&high))
+            },
+        )
+    }
+
+    ///Gets the first face hit by the ray from `origin` along `direction`, with the ray parameter
+    ///of the hit.
+    pub fn ray_cast(&self, origin: Vector3<f32>, direction: Vector3<f32>) -> Option<(usize, f32)> {
+        let inverse = direction.map(|x| 1.0 / x);
+        let mut best: Option<(usize, f32)> = None;
+        let mut stack = vec![];
+        if !self.nodes.is_empty() {
+            stack.push(0);
+        }
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if !node.hit(origin, inverse, best.map_or(f32::INFINITY, |x| x.1)) {
+                continue;
+            }
+            if node.leaf {
+                for &face in self.faces[node.start..node.end].iter() {
+                    match self.triangles[face].ray_intersection(&origin, &direction) {
+                        Some(t) if t >= 0.0 && t < best.map_or(f32::INFINITY, |x| x.1) => {
+                            best = Some((face, t));
+                        }
+                        _ => {}
+                    }
+                }
+            } else {
+                stack.push(node.end);
+                stack.push(index + 1);
+            }
+        }
+        best.map(|(face, t)| (self.ids[face], t))
+    }
+
+    ///Gets the face closest to `point` and the closest point on it.
+    pub fn nearest(&self, point: Vector3<f32>) -> Option<(usize, Vector3<f32>)> {
+        let mut best: Option<(usize, Vector3<f32>, f32)> = None;
+        let mut stack = vec![];
+        if !self.nodes.is_empty() {
+            stack.push(0);
+        }
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if node.distance_squared(point) >= best.map_or(f32::INFINITY, |x| x.2) {
+                continue;
+            }
+            if node.leaf {
+                for &face in self.faces[node.start..node.end].iter() {
+                    let (closest, _) = self.triangles[face].closest_point(&point);
+                    let distance = (closest - point).norm_squared();
+                    if distance < best.map_or(f32::INFINITY, |x| x.2) {
+                        best = Some((face, closest, distance));
+                    }
+                }
+            } else {
+                // Visit the nearer child first, so the farther one is pruned more often.
+                let (first, second) = (index + 1, node.end);
+                if self.nodes[first].distance_squared(point)
+                    <= self.nodes[second].distance_squared(point)
+                {
+                    stack.push(second);
+                    stack.push(first);
+                } else {
+                    stack.push(first);
+                    stack.push(second);
+                }
+            }
+        }
+        best.map(|(face, closest, _)| (self.ids[face], closest))
+    }
+}
+
+impl Node {
+    ///Checks whether the ray hits the box before parameter `limit`, with the slab method.
+    fn hit(&self, origin: Vector3<f32>, inverse: Vector3<f32>, limit: f32) -> bool {
+        let mut near = 0.0f32;
+        let mut far = limit;
+        for axis in 0..3 {
+            let a = (self.min[axis] - origin[axis]) * inverse[axis];
+            let b = (self.max[axis] - origin[axis]) * inverse[axis];
+            // A ray parallel to the slab gives NaN on its plane, which `max` and `min` skip.
+            near = near.max(a.min(b));
+            far = far.min(a.max(b));
+        }
+        near <= far
+    }
+
+    fn distance_squared(&self, point: Vector3<f32>) -> f32 {
+        (self.min - point)
+            .sup(&Vector3::zeros())
+            .sup(&(point - self.max))
+            .norm_squared()
+    }
+}
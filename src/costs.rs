@@ -0,0 +1,347 @@
+use crate::path_point::PathPoint;
+use crate::{Data, Mesh, PathOptions, PathResult, QueryState, NO_FACE, SCALE};
+use pathfinding::prelude::dijkstra;
+use std::collections::HashMap;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+
+///Range costs are clamped to, so that weighted edge weights can't overflow.
+const MIN_COST: f32 = 1e-3;
+const MAX_COST: f32 = 1e3;
+///Sweeps over the path per refraction pass.
+const REFRACTION_SWEEPS: usize = 8;
+///Golden section steps per edge point, narrowing its range to about 1e-6.
+const GOLDEN_STEPS: usize = 30;
+///Relative cost reduction below which refraction counts as converged.
+pub(crate) const REFRACTION_TOLERANCE: f32 = 1e-5;
+///Points added on every edge for the weighted Dijkstra search, so that its path can cross faces
+///and bend at cost boundaries instead of running along edges only.
+const STEINER_POINTS: usize = 3;
+
+///Traversal cost multiplier of every face.
+#[derive(Debug, Clone)]
+pub(crate) struct Costs {
+    faces: Vec<f32>,
+    ///Costs of every vertex, interpolated along path segments, if set per vertex. Faces then
+    ///get the mean of their vertex costs for the Dijkstra search.
+    vertices: Vec<f32>,
+    ///Whether costs differ between faces. Uniform costs scale all paths alike, so shortening
+    ///runs as without costs.
+    varying: bool,
+    ///Edges carrying Steiner points, if costs vary, and the edges of every face.
+    edges: Vec<[usize; 2]>,
+    face_edges: Vec<[usize; 3]>,
+}
+
+///Computes the path like `compute_path_with_options`, minimizing the sum of segment lengths
+///multiplied by `face_costs` instead of the length.
+#[wasm_bindgen]
+pub fn compute_weighted_path(
+    start: usize,
+    end: usize,
+    face_costs: Vec<f32>,
+    options: &PathOptions,
+    raw_coordinates: Vec<f32>,
+    indices: Vec<usize>,
+) -> PathResult {
+    let data = Data::new(raw_coordinates, indices);
+    data.set_face_costs(&face_costs);
+    let shortening = data.shorten_path_with_options(start, end, options);
+    data.path_result(&shortening, options)
+}
+
+#[wasm_bindgen]
+impl Mesh {
+    ///Sets a cost multiplier for every face, e.g. 1 for road and 5 for swamp. Paths minimize the
+    ///sum of segment lengths multiplied by the costs of the faces they cross, so they bend along
+    ///cheap regions and refract at cost boundaries. Missing and invalid costs count as 1, the
+    ///rest is clamped to 0.001..1000. Distance fields and discs still measure length. Meshes built
+    ///`from_polygons` take a cost per polygon. Running queries keep the costs they started with.
+    pub fn set_face_costs(&self, costs: Vec<f32>) {
+        self.data.set_face_costs(&self.face_costs(costs));
+    }
+
+    ///Sets costs per vertex like `set_face_costs`. Costs are interpolated linearly across faces,
+    ///so path segments cost their length multiplied by the mean cost of their ends.
+    pub fn set_vertex_costs(&self, costs: Vec<f32>) {
+        let vertices: Vec<f32> = (0..self.data.coordinates.len())
+            .map(|vertex| sanitize(costs.get(vertex).copied()))
+            .collect();
+        self.data.set_vertex_costs(vertices);
+    }
+
+    ///Removes costs, so that paths minimize length again.
+    pub fn clear_costs(&self) {
+        *self.data.costs.borrow_mut() = None;
+    }
+}
+
+fn sanitize(cost: Option<f32>) -> f32 {
+    match cost {
+        Some(cost) if cost.is_finite() && cost > 0.0 => cost.clamp(MIN_COST, MAX_COST),
+        _ => 1.0,
+    }
+}
+
+impl Data {
+    pub(crate) fn set_face_costs(&self, costs: &[f32]) {
+        let faces: Vec<f32> = (0..self.faces.len())
+            .map(|face| sanitize(costs.get(face).copied()))
+            .collect();
+        self.set_costs(faces, vec![]);
+    }
+
+    ///Gets the face and vertex costs for `Mesh::to_bytes`, empty if not set.
+    pub(crate) fn cost_buffers(&self) -> (Vec<f32>, Vec<f32>) {
+        match self.costs.borrow().as_deref() {
+            Some(costs) => (costs.faces.clone(), costs.vertices.clone()),
+            None => (vec![], vec![]),
+        }
+    }
+
+    ///Sets the costs read by `Mesh::from_bytes`. Vertex costs are used if there is one per
+    ///vertex, else face costs if any.
+    pub(crate) fn restore_costs(&self, faces: &[f32], vertices: &[f32]) {
+        if !vertices.is_empty() && vertices.len() == self.coordinates.len() {
+            let vertices = vertices.iter().map(|&x| sanitize(Some(x))).collect();
+            self.set_vertex_costs(vertices);
+        } else if !faces.is_empty() {
+            self.set_face_costs(faces);
+        }
+    }
+
+    ///Sets sanitized costs of every vertex.
+    pub(crate) fn set_vertex_costs(&self, vertices: Vec<f32>) {
+        let faces = self
+            .faces
+            .iter()
+            .map(|face| match face {
+                &NO_FACE => 1.0,
+                face => face.iter().map(|&vertex| vertices[vertex]).sum::<f32>() / 3.0,
+            })
+            .collect();
+        self.set_costs(faces, vertices);
+    }
+
+    fn set_costs(&self, faces: Vec<f32>, vertices: Vec<f32>) {
+        let varying = faces.windows(2).any(|pair| pair[0] != pair[1])
+            || vertices.windows(2).any(|pair| pair[0] != pair[1]);
+        let mut edges = vec![];
+        let mut face_edges = vec![];
+        if varying {
+            let mut ids: HashMap<[usize; 2], usize> = HashMap::new();
+            for face in self.faces.iter() {
+                if *face == NO_FACE {
+                    face_edges.push(NO_FACE);
+                    continue;
+                }
+                face_edges.push([0, 1, 2].map(|i| {
+                    let (a, b) = (face[i], face[(i + 1) % 3]);
+                    *ids.entry([a.min(b), a.max(b)]).or_insert_with(|| {
+                        edges.push([a.min(b), a.max(b)]);
+                        edges.len() - 1
+                    })
+                }));
+            }
+        }
+        *self.costs.borrow_mut() = Some(Rc::new(Costs {
+            faces,
+            vertices,
+            varying,
+            edges,
+            face_edges,
+        }));
+    }
+
+    ///Gets the neighbours of `vertex` in `path_graph` over edges next to a face that isn't
+    ///blocked, with edge weights multiplied by the cost of the cheaper such face. Uniform costs
+    ///leave weights as they are, so that rounding can't break ties differently.
+    pub(crate) fn neighbors(&self, query: &QueryState, vertex: &usize) -> Vec<(usize, u64)> {
+        let mut neighbors = self.path_graph.get(vertex).cloned().unwrap_or_default();
+        if query.has_blocked_faces() {
+            neighbors.retain(|(neighbor, _)| {
+                let faces = self.edge_faces(*vertex, *neighbor);
+                faces.iter().any(|&face| !query.is_blocked(face))
+            });
+        }
+        let costs = match query.costs.as_deref() {
+            Some(costs) if costs.varying => costs,
+            _ => return neighbors,
+        };
+        neighbors
+            .into_iter()
+            .map(|(neighbor, weight)| {
+                let cost = self
+                    .edge_faces(*vertex, neighbor)
+                    .iter()
+                    .filter(|&&face| !query.is_blocked(face))
+                    .map(|&face| costs.faces[face])
+                    .fold(f32::INFINITY, f32::min);
+                let cost = if cost.is_finite() { cost } else { 1.0 };
+                (neighbor, (weight as f64 * cost as f64) as u64)
+            })
+            .collect()
+    }
+
+    ///Gets the cost multiplier of the path segment between `a` and `b`: the cost of the face it
+    ///crosses, or of the cheaper face along the edge it runs on. With vertex costs it's the mean
+    ///of the costs at its ends, which is exact for costs interpolated linearly along it.
+    pub(crate) fn segment_cost(&self, query: &QueryState, a: &PathPoint, b: &PathPoint) -> f32 {
+        let costs = match query.costs.as_deref() {
+            Some(costs) => costs,
+            None => return 1.0,
+        };
+        if !costs.vertices.is_empty() {
+            let cost = |point: &PathPoint| match point {
+                PathPoint::Vertex(vertex) => costs.vertices[*vertex],
+                PathPoint::Edge([a, b], t) => {
+                    costs.vertices[*a] + (costs.vertices[*b] - costs.vertices[*a]) * t
+                }
+            };
+            return (cost(a) + cost(b)) / 2.0;
+        }
+        let vertices = [a.vertices(), b.vertices()].concat();
+        let cost = self.vertex_faces[vertices[0]]
+            .iter()
+            .filter(|&&face| {
+                vertices
+                    .iter()
+                    .all(|vertex| self.faces[face].contains(vertex))
+            })
+            .map(|&face| costs.faces[face])
+            .fold(f32::INFINITY, f32::min);
+        if cost.is_finite() {
+            cost
+        } else {
+            1.0
+        }
+    }
+
+    ///Finds the cheapest path between `start` and `end` over vertices and Steiner points on
+    ///edges, connected across every face at its cost. Nodes past the vertices are Steiner
+    ///points, `STEINER_POINTS` per edge of `Costs::edges`.
+    pub(crate) fn weighted_dijkstra(
+        &self,
+        query: &QueryState,
+        start: usize,
+        end: usize,
+    ) -> Option<Vec<PathPoint>> {
+        let costs = query.costs.as_deref()?;
+        let vertex_count = self.coordinates.len();
+        if start >= vertex_count || end >= vertex_count {
+            return None;
+        }
+        let point = |node: usize| {
+            if node < vertex_count {
+                PathPoint::Vertex(node)
+            } else {
+                let (edge, i) = (
+                    (node - vertex_count) / STEINER_POINTS,
+                    (node - vertex_count) % STEINER_POINTS,
+                );
+                PathPoint::Edge(
+                    costs.edges[edge],
+                    (i + 1) as f32 / (STEINER_POINTS + 1) as f32,
+                )
+            }
+        };
+        let successors = |&node: &usize| {
+            let faces = match point(node) {
+                PathPoint::Vertex(vertex) => self.vertex_faces[vertex].clone(),
+                PathPoint::Edge([a, b], _) => self.edge_faces(a, b),
+            };
+            let position = self.point_position(&point(node));
+            let mut result = vec![];
+            for face in faces.into_iter().filter(|&face| !query.is_blocked(face)) {
+                let steiner = costs.face_edges[face].iter().flat_map(|edge| {
+                    (0..STEINER_POINTS).map(move |i| vertex_count + edge * STEINER_POINTS + i)
+                });
+                for other in self.faces[face].iter().copied().chain(steiner) {
+                    if other != node {
+                        let length = (self.point_position(&point(other)) - position).magnitude();
+                        result.push((other, (length * costs.faces[face] * 1000.0) as u64));
+                    }
+                }
+            }
+            result
+        };
+        let (path, _) = dijkstra(&start, successors, |&node| node == end)?;
+        Some(path.into_iter().map(point).collect())
+    }
+
+    ///Gets the cost of the path in input units, summed in `f64` like `cumulative_lengths`.
+    pub(crate) fn path_cost(&self, query: &QueryState, path: &[PathPoint]) -> f64 {
+        path.windows(2)
+            .map(|pair| {
+                let segment = self.point_position(&pair[1]) - self.point_position(&pair[0]);
+                let length = segment.cast::<f64>().magnitude() / SCALE as f64;
+                length * self.segment_cost(query, &pair[0], &pair[1]) as f64
+            })
+            .sum()
+    }
+
+    ///Moves edge points of `path` along their edges to minimize its cost, which bends it at cost
+    ///boundaries by the law of refraction. Every point minimizes the cost of its two segments by
+    ///golden section search, which is exact for face costs, as the cost is convex along the edge.
+    ///Returns the relative cost reduction.
+    pub(crate) fn refract(&self, query: &QueryState, path: &mut [PathPoint]) -> f32 {
+        let before = self.path_length(query, path);
+        for _ in 0..REFRACTION_SWEEPS {
+            for i in 1..path.len().saturating_sub(1) {
+                let (edge, t) = match path[i] {
+                    PathPoint::Edge(edge, t) => (edge, t),
+                    PathPoint::Vertex(_) => continue,
+                };
+                let (previous, next) = (path[i - 1], path[i + 1]);
+                let (from, to) = (self.point_position(&previous), self.point_position(&next));
+                // Vertex costs change along the edge, face costs don't.
+                let cost = |t: f32| {
+                    let point = PathPoint::Edge(edge, t);
+                    let position = self.point_position(&point);
+                    (position - from).magnitude() * self.segment_cost(query, &previous, &point)
+                        + (to - position).magnitude() * self.segment_cost(query, &point, &next)
+                };
+                let best = golden_section(&cost, 0.0, 1.0);
+                if cost(best) < cost(t) {
+                    path[i] = PathPoint::Edge(edge, best);
+                }
+            }
+        }
+        let after = self.path_length(query, path);
+        if before > 0.0 {
+            (before - after) / before
+        } else {
+            0.0
+        }
+    }
+}
+
+impl QueryState {
+    pub(crate) fn has_varying_costs(&self) -> bool {
+        self.costs.as_ref().is_some_and(|x| x.varying)
+    }
+}
+
+///Finds the minimum of convex `f` on `[low, high]`.
+fn golden_section(f: &dyn Fn(f32) -> f32, mut low: f32, mut high: f32) -> f32 {
+    let ratio = (5f32.sqrt() - 1.0) / 2.0;
+    let mut a = high - (high - low) * ratio;
+    let mut b = low + (high - low) * ratio;
+    let (mut fa, mut fb) = (f(a), f(b));
+    for _ in 0..GOLDEN_STEPS {
+        if fa < fb {
+            high = b;
+            b = a;
+            fb = fa;
+            a = high - (high - low) * ratio;
+            fa = f(a);
+        } else {
+            low = a;
+            a = b;
+            fa = fb;
+            b = low + (high - low) * ratio;
+            fb = f(b);
+        }
+    }
+    (low + high) / 2.0
+}
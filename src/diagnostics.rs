@@ -0,0 +1,94 @@
+use crate::{Data, QueryState};
+use log::LevelFilter;
+use wasm_bindgen::prelude::*;
+
+///Counters of the work done by a path query.
+#[wasm_bindgen]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct PathStats {
+    ///Time spent in the Dijkstra search, in milliseconds.
+    pub dijkstra_time: f64,
+    ///Length of the Dijkstra path along mesh edges, in input units.
+    pub dijkstra_length: f64,
+    ///Shortening iterations.
+    pub iterations: usize,
+    ///Face strips unfolded into the plane.
+    pub wedges_unfolded: usize,
+    ///Path segments the mesh was cut along.
+    pub cuts: usize,
+    ///Sub triangles created by the cuts.
+    pub sub_triangles: usize,
+}
+
+///Logger writing records to the browser console.
+#[cfg(all(feature = "console_log", target_arch = "wasm32"))]
+mod console {
+    use log::{Log, Metadata, Record};
+    use wasm_bindgen::prelude::*;
+
+    #[wasm_bindgen]
+    extern "C" {
+        #[wasm_bindgen(js_namespace = console, js_name = log)]
+        fn console_log(message: &str);
+    }
+
+    pub struct ConsoleLogger;
+
+    impl Log for ConsoleLogger {
+        fn enabled(&self, metadata: &Metadata) -> bool {
+            metadata.level() <= log::max_level()
+        }
+
+        fn log(&self, record: &Record) {
+            if self.enabled(record.metadata()) {
+                console_log(&format!(
+                    "{} {}: {}",
+                    record.level(),
+                    record.target(),
+                    record.args()
+                ));
+            }
+        }
+
+        fn flush(&self) {}
+    }
+
+    pub static LOGGER: ConsoleLogger = ConsoleLogger;
+}
+
+///Turns debug logging on or off. Records go to the browser console if the `console_log`
+///feature is enabled, or to any other `log` logger installed by the host.
+#[wasm_bindgen]
+pub fn set_debug(enabled: bool) {
+    // Fails if a logger is already installed, which then gets the records instead.
+    #[cfg(all(feature = "console_log", target_arch = "wasm32"))]
+    let _ = log::set_logger(&console::LOGGER);
+    log::set_max_level(if enabled {
+        LevelFilter::Debug
+    } else {
+        LevelFilter::Off
+    });
+}
+
+impl QueryState {
+    pub(crate) fn record(&self, update: impl FnOnce(&mut PathStats)) {
+        update(&mut self.stats.borrow_mut());
+    }
+
+    pub(crate) fn stats(&self) -> PathStats {
+        *self.stats.borrow()
+    }
+}
+
+impl Data {
+    ///Keeps the stats of `query` as those of the last query and returns them.
+    pub(crate) fn finish(&self, query: &QueryState) -> PathStats {
+        let stats = query.stats();
+        *self.stats.borrow_mut() = stats;
+        stats
+    }
+
+    pub(crate) fn stats(&self) -> PathStats {
+        *self.stats.borrow()
+    }
+}
@@ -0,0 +1,183 @@
+use crate::distance_field::Seed;
+use crate::{Data, SCALE};
+use na::{Vector2, Vector3};
+use nalgebra as na;
+use wasm_bindgen::prelude::*;
+
+///Part of the surface within a geodesic radius of a point.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default)]
+pub struct GeodesicDisc {
+    vertices: Vec<usize>,
+    distances: Vec<f32>,
+    faces: Vec<usize>,
+    partial_faces: Vec<usize>,
+    boundary: Vec<Vector3<f32>>,
+}
+
+#[wasm_bindgen]
+impl GeodesicDisc {
+    ///Vertices within the radius, closest first.
+    #[wasm_bindgen(getter)]
+    pub fn vertices(&self) -> Vec<usize> {
+        self.vertices.clone()
+    }
+
+    ///Geodesic distance of every vertex of `vertices`.
+    #[wasm_bindgen(getter)]
+    pub fn distances(&self) -> Vec<f32> {
+        self.distances.clone()
+    }
+
+    ///Faces that lie within the radius completely.
+    #[wasm_bindgen(getter)]
+    pub fn faces(&self) -> Vec<usize> {
+        self.faces.clone()
+    }
+
+    ///Faces crossed by the disc boundary.
+    #[wasm_bindgen(getter)]
+    pub fn partial_faces(&self) -> Vec<usize> {
+        self.partial_faces.clone()
+    }
+
+    ///Disc boundary clipped by `partial_faces`: a pair of points per segment.
+    #[wasm_bindgen(getter)]
+    pub fn boundary(&self) -> Vec<f32> {
+        let mut result = vec![];
+        for point in self.boundary.iter() {
+            result.push(point.x);
+            result.push(point.y);
+            result.push(point.z);
+        }
+        result
+    }
+}
+
+///Selects the surface within geodesic distance `radius` of point `barycentric` of `face`. A face
+///out of range or fewer than 3 coordinates give an empty disc.
+#[wasm_bindgen]
+pub fn geodesic_disc(
+    face: usize,
+    barycentric: Vec<f32>,
+    radius: f32,
+    raw_coordinates: Vec<f32>,
+    indices: Vec<usize>,
+) -> GeodesicDisc {
+    if barycentric.len() < 3 {
+        return GeodesicDisc::default();
+    }
+    let data = Data::new(raw_coordinates, indices);
+    data.geodesic_disc(
+        face,
+        Vector3::new(barycentric[0], barycentric[1], barycentric[2]),
+        radius * SCALE,
+    )
+}
+
+impl Data {
+    ///Propagates distances from a surface point like `propagate`. The search stops as soon as the
+    ///frontier leaves `radius`, so only the disc neighbourhood is scanned.
+    pub fn geodesic_disc(
+        &self,
+        face: usize,
+        barycentric: Vector3<f32>,
+        radius: f32,
+    ) -> GeodesicDisc {
+        let indices = match self.face(face) {
+            Some(indices) => indices,
+            None => return GeodesicDisc::default(),
+        };
+        let source = self
+            .get_triangle(indices)
+            .barycentric_to_cartesian(&barycentric);
+        let seeds: Vec<Seed> = indices
+            .iter()
+            .map(|&vertex| Seed {
+                vertex,
+                distance: (self.coordinates[vertex] - source).magnitude(),
+                label: 0,
+            })
+            .collect();
+        let propagation = self.propagate(&seeds, radius);
+        let distances = propagation.distances;
+
+        let mut result = GeodesicDisc {
+            distances: propagation
+                .order
+                .iter()
+                .map(|&vertex| distances[vertex] / SCALE)
+                .collect(),
+            vertices: propagation.order,
+            faces: vec![],
+            partial_faces: vec![face],
+            boundary: vec![],
+        };
+
+        let mut touched: Vec<usize> = result
+            .vertices
+            .iter()
+            .flat_map(|&vertex| self.vertex_faces[vertex].iter().copied())
+            .collect();
+        touched.sort_unstable();
+        touched.dedup();
+        for face in touched {
+            let indices = self.faces[face];
+            if indices.iter().all(|&vertex| distances[vertex] <= radius) {
+                result.faces.push(face);
+                result.partial_faces.retain(|&x| x != face);
+                continue;
+            }
+            if !result.partial_faces.contains(&face) {
+                result.partial_faces.push(face);
+            }
+            let mut crossings = vec![];
+            for i in 0..3 {
+                let [a, b] = [indices[i], indices[(i + 1) % 3]];
+                if (distances[a] <= radius) != (distances[b] <= radius) {
+                    let t =
+                        ((radius - distances[a]) / (distances[b] - distances[a])).clamp(0.0, 1.0);
+                    let point =
+                        self.coordinates[a] + (self.coordinates[b] - self.coordinates[a]) * t;
+                    crossings.push(point / SCALE);
+                }
+            }
+            if crossings.len() == 2 {
+                result.boundary.append(&mut crossings);
+            }
+        }
+        result
+    }
+
+    ///Gets distance to the remaining vertex of `face` from a virtual source placed by distances
+    ///of the edge `edge`, which must follow the face winding. Returns `None` if the straight line
+    ///from the source misses the edge.
+    pub(crate) fn unfolded_distance(
+        &self,
+        face: usize,
+        edge: [usize; 2],
+        distances: &[f32],
+    ) -> Option<f32> {
+        let [da, db] = [distances[edge[0]], distances[edge[1]]];
+        let length = (self.coordinates[edge[1]] - self.coordinates[edge[0]]).magnitude();
+        if !da.is_finite() || !db.is_finite() || length == 0.0 {
+            return None;
+        }
+        let x = (da * da - db * db + length * length) / (2.0 * length);
+        let y = da * da - x * x;
+        if y < 0.0 {
+            return None;
+        }
+        // The source lies on the other side of the edge than the remaining vertex.
+        let source = Vector2::new(x, -y.sqrt());
+        let vertex = self.unfold_face(face, edge, [Vector2::zeros(), Vector2::new(length, 0.0)]);
+        if vertex.y <= 0.0 {
+            return None;
+        }
+        let along = source.x + (vertex.x - source.x) * (-source.y / (vertex.y - source.y));
+        if along <= 0.0 || along >= length {
+            return None;
+        }
+        Some((vertex - source).magnitude())
+    }
+}
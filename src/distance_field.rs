@@ -0,0 +1,240 @@
+use crate::utils::QueueEntry;
+use crate::{Data, Mesh, SCALE};
+use na::Vector3;
+use nalgebra as na;
+use std::collections::BinaryHeap;
+use wasm_bindgen::prelude::*;
+
+///Distance from every vertex to the nearest of several sources.
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct DistanceField {
+    distances: Vec<f32>,
+    sources: Vec<i32>,
+}
+
+#[wasm_bindgen]
+impl DistanceField {
+    ///Distance of every vertex to its nearest source. Infinite if no source is connected to it.
+    #[wasm_bindgen(getter)]
+    pub fn distances(&self) -> Vec<f32> {
+        self.distances.clone()
+    }
+
+    ///Index of the nearest source of every vertex in the given sources, or -1 if there is none.
+    #[wasm_bindgen(getter)]
+    pub fn sources(&self) -> Vec<i32> {
+        self.sources.clone()
+    }
+}
+
+///Computes the distance field of the `sources` vertices. Sources out of range are ignored.
+#[wasm_bindgen]
+pub fn distance_field(
+    sources: Vec<usize>,
+    raw_coordinates: Vec<f32>,
+    indices: Vec<usize>,
+) -> DistanceField {
+    let data = Data::new(raw_coordinates, indices);
+    data.distance_field(&data.vertex_seeds(&sources))
+}
+
+///Computes the distance field of surface points given by `faces` and their barycentric
+///coordinates, three per face. Points on faces out of range are ignored.
+#[wasm_bindgen]
+pub fn distance_field_from_points(
+    faces: Vec<usize>,
+    barycentric: Vec<f32>,
+    raw_coordinates: Vec<f32>,
+    indices: Vec<usize>,
+) -> DistanceField {
+    let data = Data::new(raw_coordinates, indices);
+    data.distance_field(&data.point_seeds(&faces, &barycentric))
+}
+
+#[wasm_bindgen]
+impl Mesh {
+    ///Computes the distance field of the `sources` vertices like `distance_field`. Results are
+    ///given for every input vertex.
+    pub fn distance_field(&self, sources: Vec<usize>) -> DistanceField {
+        let sources: Vec<usize> = sources.iter().map(|&x| self.vertex(x)).collect();
+        let field = self.data.distance_field(&self.data.vertex_seeds(&sources));
+        self.input_field(field)
+    }
+
+    ///Computes the distance field of surface points like `distance_field_from_points`. Points are
+    ///given on triangles, like `SurfacePoint::triangle`, also on meshes built `from_polygons`.
+    pub fn distance_field_from_points(
+        &self,
+        triangles: Vec<usize>,
+        barycentric: Vec<f32>,
+    ) -> DistanceField {
+        let field = self
+            .data
+            .distance_field(&self.data.point_seeds(&triangles, &barycentric));
+        self.input_field(field)
+    }
+}
+
+impl Mesh {
+    ///Gets `field` for every input vertex, with the values of the vertex it was welded to.
+    fn input_field(&self, field: DistanceField) -> DistanceField {
+        let welded = match self.welded.as_ref() {
+            Some(welded) => welded,
+            None => return field,
+        };
+        DistanceField {
+            distances: welded
+                .iter()
+                .map(|&x| field.distances.get(x).copied().unwrap_or(f32::INFINITY))
+                .collect(),
+            sources: welded
+                .iter()
+                .map(|&x| field.sources.get(x).copied().unwrap_or(-1))
+                .collect(),
+        }
+    }
+}
+
+///Vertex the propagation starts from, at `distance` from source `label`.
+pub(crate) struct Seed {
+    pub vertex: usize,
+    pub distance: f32,
+    pub label: usize,
+}
+
+pub(crate) struct Propagation {
+    ///Distances in scaled units. Only vertices in `order` are final.
+    pub distances: Vec<f32>,
+    ///Source of every vertex reached.
+    pub labels: Vec<Option<usize>>,
+    ///Vertices within the radius, closest first.
+    pub order: Vec<usize>,
+}
+
+impl Data {
+    fn vertex_seeds(&self, sources: &[usize]) -> Vec<Seed> {
+        sources
+            .iter()
+            .enumerate()
+            .filter(|(_, &vertex)| vertex < self.vertex_faces.len())
+            .map(|(label, &vertex)| Seed {
+                vertex,
+                distance: 0.0,
+                label,
+            })
+            .collect()
+    }
+
+    fn point_seeds(&self, faces: &[usize], barycentric: &[f32]) -> Vec<Seed> {
+        let mut seeds = vec![];
+        for (label, (&face, coordinates)) in
+            faces.iter().zip(barycentric.chunks_exact(3)).enumerate()
+        {
+            let indices = match self.face(face) {
+                Some(indices) => indices,
+                None => continue,
+            };
+            let point = self
+                .get_triangle(indices)
+                .barycentric_to_cartesian(&Vector3::from_column_slice(coordinates));
+            for &vertex in indices.iter() {
+                seeds.push(Seed {
+                    vertex,
+                    distance: (self.coordinates[vertex] - point).magnitude(),
+                    label,
+                });
+            }
+        }
+        seeds
+    }
+
+    fn distance_field(&self, seeds: &[Seed]) -> DistanceField {
+        let propagation = self.propagate(seeds, f32::INFINITY);
+        DistanceField {
+            distances: propagation
+                .distances
+                .iter()
+                .map(|distance| distance / SCALE)
+                .collect(),
+            sources: propagation
+                .labels
+                .iter()
+                .map(|label| label.map_or(-1, |x| x as i32))
+                .collect(),
+        }
+    }
+
+    ///Runs Dijkstra over `path_graph` from `seeds`, improving edge distances by unfolding faces
+    ///with two known vertices. Faces are only unfolded from vertices reached from the same
+    ///source, so every vertex gets the distance and label of its nearest one. The search stops
+    ///as soon as the frontier leaves `radius`.
+    pub(crate) fn propagate(&self, seeds: &[Seed], radius: f32) -> Propagation {
+        let vertex_count = self.vertex_faces.len();
+        let mut distances = vec![f32::INFINITY; vertex_count];
+        let mut labels = vec![None; vertex_count];
+        let mut done = vec![false; vertex_count];
+        let mut queue = BinaryHeap::new();
+        let mut order = vec![];
+
+        for seed in seeds.iter() {
+            if seed.distance < distances[seed.vertex] {
+                distances[seed.vertex] = seed.distance;
+                labels[seed.vertex] = Some(seed.label);
+                queue.push(QueueEntry {
+                    distance: seed.distance,
+                    vertex: seed.vertex,
+                });
+            }
+        }
+
+        while let Some(QueueEntry { distance, vertex }) = queue.pop() {
+            if distance > radius {
+                break;
+            }
+            if done[vertex] {
+                continue;
+            }
+            done[vertex] = true;
+            order.push(vertex);
+
+            let label = labels[vertex];
+            let mut candidates = vec![];
+            if let Some(neighbors) = self.path_graph.get(&vertex) {
+                for (neighbor, _) in neighbors.iter() {
+                    let length =
+                        (self.coordinates[*neighbor] - self.coordinates[vertex]).magnitude();
+                    candidates.push((*neighbor, distance + length));
+                }
+            }
+            for &face in self.vertex_faces[vertex].iter() {
+                let [_, p, q] = self.rotated_face(face, vertex);
+                if done[p] && labels[p] == label {
+                    if let Some(candidate) = self.unfolded_distance(face, [vertex, p], &distances) {
+                        candidates.push((q, candidate));
+                    }
+                }
+                if done[q] && labels[q] == label {
+                    if let Some(candidate) = self.unfolded_distance(face, [q, vertex], &distances) {
+                        candidates.push((p, candidate));
+                    }
+                }
+            }
+            for (neighbor, candidate) in candidates {
+                if !done[neighbor] && candidate < distances[neighbor] {
+                    distances[neighbor] = candidate;
+                    labels[neighbor] = label;
+                    queue.push(QueueEntry {
+                        distance: candidate,
+                        vertex: neighbor,
+                    });
+                }
+            }
+        }
+        Propagation {
+            distances,
+            labels,
+            order,
+        }
+    }
+}
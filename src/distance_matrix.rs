@@ -0,0 +1,128 @@
+use crate::options::now;
+use crate::path_point::PathPoint;
+use crate::{Data, Mesh, PathOptions, QueryState};
+use pathfinding::prelude::{build_path, dijkstra_partial};
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+
+///How `distance_matrix` measures distances.
+#[wasm_bindgen]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DistanceMethod {
+    ///Lengths of the shortest paths along mesh edges. Fast, but longer than the geodesic
+    ///distance by up to several percent depending on the triangulation.
+    Edges,
+    ///Lengths of the edge paths shortened like `compute_path`.
+    Shortened,
+}
+
+///Computes geodesic distances between all pairs of `landmarks` vertices. Returns a symmetric
+///row-major matrix with a row per landmark. Unconnected pairs are infinite. On a `Mesh` with
+///costs, distances are lengths of the cheapest paths. `options` limits the
+///shortening of every pair with `DistanceMethod::Shortened`.
+#[wasm_bindgen]
+pub fn distance_matrix(
+    landmarks: Vec<usize>,
+    method: DistanceMethod,
+    options: &PathOptions,
+    raw_coordinates: Vec<f32>,
+    indices: Vec<usize>,
+) -> Vec<f32> {
+    let data = Data::new(raw_coordinates, indices);
+    data.distance_matrix(&landmarks, method, options)
+}
+
+#[wasm_bindgen]
+impl Mesh {
+    ///Computes the distances between `landmarks` like `distance_matrix`.
+    pub fn distance_matrix(
+        &self,
+        landmarks: Vec<usize>,
+        method: DistanceMethod,
+        options: &PathOptions,
+    ) -> Vec<f32> {
+        let landmarks: Vec<usize> = landmarks.iter().map(|&x| self.vertex(x)).collect();
+        self.data
+            .distance_matrix(&landmarks, method, &self.face_options(options))
+    }
+}
+
+impl Data {
+    ///Runs one Dijkstra search per landmark over `path_graph`, stopping once it has reached the
+    ///landmarks after it, and takes the distances of both halves of the matrix from it. Shortened
+    ///distances start from the edge paths of the same search.
+    pub fn distance_matrix(
+        &self,
+        landmarks: &[usize],
+        method: DistanceMethod,
+        options: &PathOptions,
+    ) -> Vec<f32> {
+        let query = Rc::new(self.start_query(options));
+        let count = landmarks.len();
+        let mut matrix = vec![f32::INFINITY; count * count];
+        for (i, &source) in landmarks.iter().enumerate() {
+            matrix[i * count + i] = 0.0;
+            if source >= self.coordinates.len() {
+                continue;
+            }
+            let mut pending: HashSet<usize> = landmarks[i + 1..]
+                .iter()
+                .copied()
+                .filter(|&x| x != source)
+                .collect();
+            let mut parents = HashMap::new();
+            if !pending.is_empty() {
+                let started = now();
+                parents = dijkstra_partial(
+                    &source,
+                    |index| self.neighbors(&query, index),
+                    |index| pending.remove(index) && pending.is_empty(),
+                )
+                .0;
+                let dijkstra_time = now() - started;
+                query.record(|stats| stats.dijkstra_time += dijkstra_time);
+            }
+
+            for (j, &target) in landmarks.iter().enumerate().skip(i + 1) {
+                let distance = if target == source {
+                    Some(0.0)
+                } else {
+                    parents.get(&target).map(|_| {
+                        let path = build_path(&target, &parents);
+                        match method {
+                            DistanceMethod::Edges => {
+                                let path: Vec<PathPoint> =
+                                    path.into_iter().map(PathPoint::Vertex).collect();
+                                let lengths = self.cumulative_lengths(&path);
+                                lengths.last().copied().unwrap_or(0.0) as f32
+                            }
+                            DistanceMethod::Shortened => {
+                                self.shortened_length(&query, path, options)
+                            }
+                        }
+                    })
+                };
+                let distance = distance.unwrap_or(f32::INFINITY);
+                matrix[i * count + j] = distance;
+                matrix[j * count + i] = distance;
+            }
+        }
+        self.finish(&query);
+        matrix
+    }
+
+    ///Shortens the vertex path `path` within the limits of `options` and gets its length.
+    fn shortened_length(
+        &self,
+        query: &Rc<QueryState>,
+        path: Vec<usize>,
+        options: &PathOptions,
+    ) -> f32 {
+        let path = path.into_iter().map(PathPoint::Vertex).collect();
+        let mut shortening = self.shortening_from(query.clone(), Some(path), now());
+        while self.shorten_step(&mut shortening, options).is_none() {}
+        let lengths = self.cumulative_lengths(&shortening.path);
+        lengths.last().copied().unwrap_or(0.0) as f32
+    }
+}
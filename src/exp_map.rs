@@ -0,0 +1,195 @@
+use crate::line2::Line2;
+use crate::surface_point::SurfacePoint;
+use crate::triangle::Triangle;
+use crate::utils::QueueEntry;
+use crate::{Data, SCALE};
+use na::{Vector2, Vector3};
+use nalgebra as na;
+use std::collections::BinaryHeap;
+use std::f32::consts::PI;
+use wasm_bindgen::prelude::*;
+
+///Barycentric coordinate a vector may miss a face by and still end on it, so that vectors to the
+///chart border aren't lost to rounding.
+const BARYCENTRIC_TOLERANCE: f32 = 1e-4;
+
+///Computes geodesic polar coordinates of every vertex around `source`. Returns a pair of
+///distance and angle per vertex; vertices that can't be reached get `NaN`, and so do all of them
+///if `source` is out of range.
+#[wasm_bindgen]
+pub fn log_map(source: usize, raw_coordinates: Vec<f32>, indices: Vec<usize>) -> Vec<f32> {
+    let data = Data::new(raw_coordinates, indices);
+    let mut result = vec![];
+    for point in data.log_map(source).iter() {
+        match point {
+            Some(point) => {
+                result.push(point.magnitude() / SCALE);
+                result.push(point.y.atan2(point.x));
+            }
+            None => {
+                result.push(f32::NAN);
+                result.push(f32::NAN);
+            }
+        }
+    }
+    result
+}
+
+///Maps tangent vector `[x, y]` given in the chart of `log_map` back onto the surface. Gives
+///nothing for a shorter vector or `source` out of range.
+#[wasm_bindgen]
+pub fn exp_map(
+    source: usize,
+    vector: Vec<f32>,
+    raw_coordinates: Vec<f32>,
+    indices: Vec<usize>,
+) -> Option<SurfacePoint> {
+    if vector.len() < 2 {
+        return None;
+    }
+    let data = Data::new(raw_coordinates, indices);
+    data.exp_map(source, Vector2::new(vector[0], vector[1]) * SCALE)
+}
+
+impl Data {
+    ///Computes positions of vertices in the tangent plane of `source`, so that the length of a
+    ///position is the geodesic distance and its direction is the polar angle. The first edge of
+    ///the source fan defines zero angle, angles around an interior source are normalized to a
+    ///full turn.
+    pub fn log_map(&self, source: usize) -> Vec<Option<Vector2<f32>>> {
+        let vertex_count = self.vertex_faces.len();
+        let mut chart = vec![None; vertex_count];
+        let mut done = vec![false; vertex_count];
+        let mut queue = BinaryHeap::new();
+        if source >= vertex_count {
+            return chart;
+        }
+        chart[source] = Some(Vector2::zeros());
+        done[source] = true;
+
+        let (fan, closed) = self.vertex_fan(source);
+        let total_angle: f32 = fan.iter().map(|&face| self.face_angle(face, source)).sum();
+        let angle_scale = if closed && total_angle > 0.0 {
+            2.0 * PI / total_angle
+        } else {
+            1.0
+        };
+
+        let mut angle = 0.0;
+        for (i, &face) in fan.iter().enumerate() {
+            let [_, a, b] = self.rotated_face(face, source);
+            if i == 0 {
+                self.place(&mut chart, &mut queue, source, a, 0.0);
+            }
+            angle += self.face_angle(face, source);
+            if i != fan.len() - 1 || !closed {
+                self.place(&mut chart, &mut queue, source, b, angle * angle_scale);
+            }
+        }
+
+        while let Some(QueueEntry { vertex, .. }) = queue.pop() {
+            if done[vertex] {
+                continue;
+            }
+            done[vertex] = true;
+            for &face in self.vertex_faces[vertex].iter() {
+                let [_, p, q] = self.rotated_face(face, vertex);
+                if done[p] && !done[q] {
+                    self.relax(&mut chart, &mut queue, face, [vertex, p], q);
+                }
+                if done[q] && !done[p] {
+                    self.relax(&mut chart, &mut queue, face, [q, vertex], p);
+                }
+            }
+        }
+        chart
+    }
+
+    ///Finds the surface point at which tangent vector `vector` of `source` ends. Vectors are in
+    ///the chart of `log_map` and in internal units.
+    pub fn exp_map(&self, source: usize, vector: Vector2<f32>) -> Option<SurfacePoint> {
+        let chart = self.log_map(source);
+        let target = vector.push(0.0);
+        (0..self.faces.len())
+            .filter_map(|face| {
+                let indices = self.face(face)?;
+                let mut points = [Vector3::zeros(); 3];
+                for (point, index) in points.iter_mut().zip(indices.iter()) {
+                    *point = chart[*index]?.push(0.0);
+                }
+                let flat = Triangle::from_array(points);
+                if flat.is_collinear()
+                    || flat.cartesian_to_barycentric(&target).min() < -BARYCENTRIC_TOLERANCE
+                {
+                    return None;
+                }
+                let radius = points
+                    .iter()
+                    .map(|point| point.magnitude())
+                    .fold(0.0, f32::max);
+                Some((face, flat, radius))
+            })
+            .min_by(|a, b| a.2.total_cmp(&b.2))
+            .map(|(face, flat, _)| {
+                let barycentric = flat.cartesian_to_barycentric(&target).map(|x| x.max(0.0));
+                let barycentric = barycentric / barycentric.sum();
+                let position = self
+                    .get_triangle(self.faces[face])
+                    .barycentric_to_cartesian(&barycentric);
+                SurfacePoint::new(face, barycentric, position / SCALE)
+            })
+    }
+
+    fn place(
+        &self,
+        chart: &mut [Option<Vector2<f32>>],
+        queue: &mut BinaryHeap<QueueEntry>,
+        source: usize,
+        vertex: usize,
+        angle: f32,
+    ) {
+        let distance = (self.coordinates[vertex] - self.coordinates[source]).magnitude();
+        chart[vertex] = Some(Vector2::new(angle.cos(), angle.sin()) * distance);
+        queue.push(QueueEntry { distance, vertex });
+    }
+
+    fn relax(
+        &self,
+        chart: &mut [Option<Vector2<f32>>],
+        queue: &mut BinaryHeap<QueueEntry>,
+        face: usize,
+        edge: [usize; 2],
+        vertex: usize,
+    ) {
+        let positions = [chart[edge[0]].unwrap(), chart[edge[1]].unwrap()];
+        let unfolded = self.unfold_face(face, edge, positions);
+        let length = unfolded.magnitude();
+        if length == 0.0 {
+            return;
+        }
+
+        // The straight line from the source reaches the vertex through the edge.
+        let visible = Line2::new(positions[0], positions[1])
+            .intersect(&Line2::new(Vector2::zeros(), unfolded))
+            .is_some();
+        let distance = if visible {
+            length
+        } else {
+            edge.iter()
+                .zip(positions.iter())
+                .map(|(index, position)| {
+                    position.magnitude()
+                        + (self.coordinates[vertex] - self.coordinates[*index]).magnitude()
+                })
+                .fold(f32::INFINITY, f32::min)
+        };
+
+        match chart[vertex] {
+            Some(current) if current.magnitude() <= distance => {}
+            _ => {
+                chart[vertex] = Some(unfolded * (distance / length));
+                queue.push(QueueEntry { distance, vertex });
+            }
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use na::Vector2;
+use nalgebra as na;
+
+///Edge a path crosses between two unfolded faces, as vertex index and plane position of its ends
+///on the left and on the right of the path.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Portal {
+    pub left: (usize, Vector2<f32>),
+    pub right: (usize, Vector2<f32>),
+}
+
+impl Portal {
+    pub fn point(vertex: usize, position: Vector2<f32>) -> Portal {
+        Portal {
+            left: (vertex, position),
+            right: (vertex, position),
+        }
+    }
+}
+
+///Finds the shortest path through `portals` with the funnel algorithm. The first and the last
+///portal are the start and the end point. Returns the path corners as portal index and vertex.
+pub(crate) fn funnel(portals: &[Portal]) -> Vec<(usize, usize)> {
+    let mut corners = vec![(0, portals[0].left.0)];
+    let mut apex = portals[0].left.1;
+    let (mut left, mut left_index) = (apex, 0);
+    let (mut right, mut right_index) = (apex, 0);
+
+    let mut i = 1;
+    while i < portals.len() {
+        let (portal_left, portal_right) = (portals[i].left.1, portals[i].right.1);
+
+        if cross(apex, right, portal_right) >= 0.0 {
+            if apex == right || cross(apex, left, portal_right) <= 0.0 {
+                right = portal_right;
+                right_index = i;
+            } else {
+                // The right side crossed the left one, so the path bends at the left end.
+                corners.push((left_index, portals[left_index].left.0));
+                apex = left;
+                right = apex;
+                right_index = left_index;
+                i = left_index + 1;
+                continue;
+            }
+        }
+
+        if cross(apex, left, portal_left) <= 0.0 {
+            if apex == left || cross(apex, right, portal_left) >= 0.0 {
+                left = portal_left;
+                left_index = i;
+            } else {
+                corners.push((right_index, portals[right_index].right.0));
+                apex = right;
+                left = apex;
+                left_index = right_index;
+                i = right_index + 1;
+                continue;
+            }
+        }
+        i += 1;
+    }
+
+    let last = portals.len() - 1;
+    corners.push((last, portals[last].left.0));
+    corners
+}
+
+///Positive if `c` lies on the left of the line from `a` to `b`.
+fn cross(a: Vector2<f32>, b: Vector2<f32>, c: Vector2<f32>) -> f32 {
+    (b - a).perp(&(c - a))
+}
@@ -0,0 +1,345 @@
+use crate::line2::Line2;
+use crate::surface_point::SurfacePoint;
+use crate::triangle::Triangle;
+use crate::{Data, SCALE};
+use na::{Matrix2, Vector2, Vector3};
+use nalgebra as na;
+use wasm_bindgen::prelude::*;
+
+///Result of tracing a straightest geodesic across the surface.
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct GeodesicTrace {
+    end: SurfacePoint,
+    faces: Vec<usize>,
+    points: Vec<Vector3<f32>>,
+    direction: Vector3<f32>,
+    length: f32,
+}
+
+#[wasm_bindgen]
+impl GeodesicTrace {
+    ///Surface point where the trace ends.
+    #[wasm_bindgen(getter)]
+    pub fn end(&self) -> SurfacePoint {
+        self.end
+    }
+
+    ///Faces crossed by the trace, in order, starting with the start face.
+    #[wasm_bindgen(getter)]
+    pub fn faces(&self) -> Vec<usize> {
+        self.faces.clone()
+    }
+
+    ///Polyline of the trace: start point, every edge or vertex crossing and end point.
+    #[wasm_bindgen(getter)]
+    pub fn points(&self) -> Vec<f32> {
+        let mut result = vec![];
+        for point in self.points.iter() {
+            result.push(point.x);
+            result.push(point.y);
+            result.push(point.z);
+        }
+        result
+    }
+
+    ///Unit direction of the trace at its end point.
+    #[wasm_bindgen(getter)]
+    pub fn direction(&self) -> Vec<f32> {
+        vec![self.direction.x, self.direction.y, self.direction.z]
+    }
+
+    ///Traced length. It is shorter than requested if the trace reached the mesh boundary.
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> f32 {
+        self.length
+    }
+}
+
+///Shoots a straightest geodesic from point `barycentric` of `face` along `direction` for
+///`length`. The direction is projected onto the start face. Gives nothing for a face out of
+///range or invalid, fewer than 3 coordinates or a direction across the face.
+#[wasm_bindgen]
+pub fn trace_geodesic(
+    face: usize,
+    barycentric: Vec<f32>,
+    direction: Vec<f32>,
+    length: f32,
+    raw_coordinates: Vec<f32>,
+    indices: Vec<usize>,
+) -> Option<GeodesicTrace> {
+    if barycentric.len() < 3 || direction.len() < 3 {
+        return None;
+    }
+    let data = Data::new(raw_coordinates, indices);
+    data.trace_geodesic(
+        face,
+        Vector3::new(barycentric[0], barycentric[1], barycentric[2]),
+        Vector3::new(direction[0], direction[1], direction[2]),
+        length * SCALE,
+    )
+}
+
+///Face unfolded into the plane of a trace, with positions in the order of the face indices.
+#[derive(Debug, Copy, Clone)]
+struct FaceChart {
+    face: usize,
+    indices: [usize; 3],
+    positions: [Vector2<f32>; 3],
+}
+
+impl FaceChart {
+    fn position(&self, vertex: usize) -> Vector2<f32> {
+        self.positions[self.indices.iter().position(|&x| x == vertex).unwrap()]
+    }
+
+    fn triangle(&self) -> Triangle {
+        Triangle::from_array(self.positions.map(|position| position.push(0.0)))
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+enum Exit {
+    Edge([usize; 2]),
+    Vertex(usize),
+}
+
+impl Data {
+    ///Traces a straightest geodesic, walking from face to face by unfolding every next face into
+    ///the plane of the previous one. Lengths are in internal units.
+    pub fn trace_geodesic(
+        &self,
+        face: usize,
+        barycentric: Vector3<f32>,
+        direction: Vector3<f32>,
+        length: f32,
+    ) -> Option<GeodesicTrace> {
+        let [a, b, _] = self.face(face)?;
+        let edge = Vector2::new((self.coordinates[b] - self.coordinates[a]).magnitude(), 0.0);
+        let mut chart = self.face_chart(face, [a, b], [Vector2::zeros(), edge]);
+        let mut point = chart.triangle().barycentric_to_cartesian(&barycentric).xy();
+        let heading = self
+            .to_flat(&chart, direction)?
+            .try_normalize(f32::EPSILON)?;
+
+        let mut faces = vec![face];
+        let mut points = vec![self.to_space(&chart, point)];
+        let mut entry: Option<Exit> = None;
+        let mut remaining = length;
+
+        for _ in 0..self.faces.len() * 4 {
+            let (exit, distance, crossing) = match self.exit(&chart, point, heading, &entry) {
+                Some(val) => val,
+                None => break,
+            };
+            if distance >= remaining {
+                point += heading * remaining;
+                remaining = 0.0;
+                break;
+            }
+
+            let next = match exit {
+                Exit::Edge(edge) => match self.opposite_face(chart.face, edge) {
+                    Some(next) => {
+                        let positions = [chart.position(edge[1]), chart.position(edge[0])];
+                        Some(self.face_chart(next, [edge[1], edge[0]], positions))
+                    }
+                    None => None,
+                },
+                Exit::Vertex(vertex) => self.turn_at_vertex(&chart, vertex, heading),
+            };
+            remaining -= distance;
+            point = crossing;
+            points.push(self.to_space(&chart, point));
+            match next {
+                Some(next) => {
+                    chart = next;
+                    faces.push(chart.face);
+                    entry = Some(exit);
+                }
+                None => break,
+            }
+        }
+
+        let barycentric = chart.triangle().cartesian_to_barycentric(&point.push(0.0));
+        let position = self.to_space(&chart, point);
+        if remaining == 0.0 {
+            points.push(position);
+        }
+        Some(GeodesicTrace {
+            end: SurfacePoint::new(chart.face, barycentric, position / SCALE),
+            faces,
+            points: points.iter().map(|point| point / SCALE).collect(),
+            direction: (self.to_space(&chart, point + heading) - position).normalize(),
+            length: (length - remaining) / SCALE,
+        })
+    }
+
+    ///Unfolds `face` given plane positions of its edge `edge`. The edge may be given in either
+    ///direction.
+    fn face_chart(&self, face: usize, edge: [usize; 2], positions: [Vector2<f32>; 2]) -> FaceChart {
+        let (edge, positions) = if self.rotated_face(face, edge[0])[1] == edge[1] {
+            (edge, positions)
+        } else {
+            ([edge[1], edge[0]], [positions[1], positions[0]])
+        };
+        let third = self.unfold_face(face, edge, positions);
+        let indices = self.faces[face];
+        FaceChart {
+            face,
+            indices,
+            positions: indices.map(|index| {
+                if index == edge[0] {
+                    positions[0]
+                } else if index == edge[1] {
+                    positions[1]
+                } else {
+                    third
+                }
+            }),
+        }
+    }
+
+    ///Finds where a ray leaves the face of `chart`, skipping the edge or vertex it entered
+    ///through. Returns the exit, the distance to it and its plane position.
+    fn exit(
+        &self,
+        chart: &FaceChart,
+        point: Vector2<f32>,
+        heading: Vector2<f32>,
+        entry: &Option<Exit>,
+    ) -> Option<(Exit, f32, Vector2<f32>)> {
+        let mut result: Option<(Exit, f32, Vector2<f32>)> = None;
+        for i in 0..3 {
+            let edge = [chart.indices[i], chart.indices[(i + 1) % 3]];
+            let skip = match entry {
+                Some(Exit::Edge(entry)) => entry.contains(&edge[0]) && entry.contains(&edge[1]),
+                Some(Exit::Vertex(vertex)) => edge.contains(vertex),
+                None => false,
+            };
+            if skip {
+                continue;
+            }
+            let start = chart.positions[i];
+            let end = chart.positions[(i + 1) % 3];
+            // Only edges the ray leaves through count, a start point lying on such an edge gives
+            // zero distance.
+            if heading.perp(&(end - start)) <= 0.0 {
+                continue;
+            }
+            if let Some((distance, along)) = ray_crossing(point, heading, start, end) {
+                if along > 0.0 && along < 1.0 && distance > -0.01 {
+                    let distance = distance.max(0.0);
+                    if result.as_ref().is_none_or(|x| distance < x.1) {
+                        result = Some((Exit::Edge(edge), distance, point + heading * distance));
+                    }
+                }
+            }
+        }
+        if result.is_some() {
+            return result;
+        }
+
+        // The ray leaves through a vertex.
+        let mut offset = f32::INFINITY;
+        for (vertex, position) in chart.indices.iter().zip(chart.positions.iter()) {
+            if let Some(Exit::Vertex(entry)) = entry {
+                if entry == vertex {
+                    continue;
+                }
+            }
+            let to_vertex = position - point;
+            let distance = to_vertex.dot(&heading);
+            let off = to_vertex.perp(&heading).abs();
+            if distance > 0.0 && off < offset {
+                offset = off;
+                result = Some((Exit::Vertex(*vertex), distance, *position));
+            }
+        }
+        result
+    }
+
+    ///Continues a trace that hits `vertex` so that it leaves with equal angles on both sides.
+    ///Returns `None` for boundary vertices.
+    fn turn_at_vertex(
+        &self,
+        chart: &FaceChart,
+        vertex: usize,
+        heading: Vector2<f32>,
+    ) -> Option<FaceChart> {
+        let (fan, closed) = self.vertex_fan(vertex);
+        if !closed {
+            return None;
+        }
+        let angles: Vec<f32> = fan
+            .iter()
+            .map(|&face| self.face_angle(face, vertex))
+            .collect();
+        let total: f32 = angles.iter().sum();
+
+        let origin = chart.position(vertex);
+        let side = chart.position(self.rotated_face(chart.face, vertex)[1]) - origin;
+        let back = (-heading)
+            .angle(&side)
+            .min(angles[fan.iter().position(|&x| x == chart.face)?]);
+        let before: f32 = fan
+            .iter()
+            .zip(angles.iter())
+            .take_while(|(&face, _)| face != chart.face)
+            .map(|(_, angle)| angle)
+            .sum();
+        let mut outgoing = (before + back + total / 2.0) % total;
+
+        for (&face, angle) in fan.iter().zip(angles.iter()) {
+            if outgoing <= *angle || face == *fan.last().unwrap() {
+                let [_, side, _] = self.rotated_face(face, vertex);
+                let length = (self.coordinates[side] - self.coordinates[vertex]).magnitude();
+                let (sin, cos) = outgoing.sin_cos();
+                let direction = Vector2::new(
+                    heading.x * cos + heading.y * sin,
+                    heading.y * cos - heading.x * sin,
+                );
+                return Some(self.face_chart(
+                    face,
+                    [vertex, side],
+                    [origin, origin + direction * length],
+                ));
+            }
+            outgoing -= angle;
+        }
+        None
+    }
+
+    ///Expresses a vector of the face of `chart` in the plane of the chart. Vectors off the face
+    ///plane are projected onto it.
+    fn to_flat(&self, chart: &FaceChart, vector: Vector3<f32>) -> Option<Vector2<f32>> {
+        let [a, b, c] = chart.indices.map(|index| self.coordinates[index]);
+        let (u, v) = (b - a, c - a);
+        let gram = Matrix2::new(u.dot(&u), u.dot(&v), u.dot(&v), v.dot(&v));
+        let weights = gram.try_inverse()? * Vector2::new(vector.dot(&u), vector.dot(&v));
+        Some(
+            (chart.positions[1] - chart.positions[0]) * weights.x
+                + (chart.positions[2] - chart.positions[0]) * weights.y,
+        )
+    }
+
+    ///Maps a point of the plane of `chart` back onto its face.
+    fn to_space(&self, chart: &FaceChart, point: Vector2<f32>) -> Vector3<f32> {
+        let barycentric = chart.triangle().cartesian_to_barycentric(&point.push(0.0));
+        self.get_triangle(chart.indices)
+            .barycentric_to_cartesian(&barycentric)
+    }
+}
+
+///Intersects ray `origin + t * heading` with segment `start`-`end`. Returns the ray parameter
+///and the relative position along the segment.
+fn ray_crossing(
+    origin: Vector2<f32>,
+    heading: Vector2<f32>,
+    start: Vector2<f32>,
+    end: Vector2<f32>,
+) -> Option<(f32, f32)> {
+    Line2::new(origin, origin + heading)
+        .intersect_lines(&Line2::new(start, end))
+        .map(|crossing| (crossing.t, crossing.u))
+}
@@ -0,0 +1,66 @@
+use crate::{Data, Mesh, SCALE};
+use na::Vector3;
+use nalgebra as na;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+impl Mesh {
+    ///Builds the terrain surface of a digital elevation model. `heights` is a row-major grid of
+    ///`columns` heights per row, and vertex `row * columns + column` lies at
+    ///`(origin_x + column * cell_size, origin_y + row * cell_size, height)`, so paths are in world
+    ///units. Every cell is split along the diagonal with the smaller height difference. Triangles
+    ///touching non-finite heights, e.g. no data values, are left out, and cells with one such
+    ///corner are split so that the triangle without it is kept. The grid is triangulated straight
+    ///into the mesh, without an intermediate index buffer. Coordinates are `f32`, so
+    ///large projected origins lose precision: subtract them and add them back to results.
+    pub fn from_heightfield(
+        heights: &[f32],
+        columns: usize,
+        cell_size: f32,
+        origin_x: f32,
+        origin_y: f32,
+    ) -> Mesh {
+        let rows = heights.len().checked_div(columns).unwrap_or(0);
+        let coordinates = heights[..rows * columns]
+            .iter()
+            .enumerate()
+            .map(|(i, height)| {
+                let x = origin_x + (i % columns) as f32 * cell_size;
+                let y = origin_y + (i / columns) as f32 * cell_size;
+                Vector3::new(x, y, *height) * SCALE
+            })
+            .collect();
+        let cells = rows.saturating_sub(1) * columns.saturating_sub(1);
+        let mut data = Data::with_vertices(coordinates, 2 * cells);
+        let finite = |vertex: usize| heights[vertex].is_finite();
+        for row in 1..rows {
+            for column in 1..columns {
+                let [a, b, c, d] = [
+                    (row - 1) * columns + column - 1,
+                    (row - 1) * columns + column,
+                    row * columns + column,
+                    row * columns + column - 1,
+                ];
+                // A diagonal away from a missing corner keeps the triangle without it.
+                let along_ac = if !finite(a) || !finite(c) {
+                    false
+                } else if !finite(b) || !finite(d) {
+                    true
+                } else {
+                    (heights[a] - heights[c]).abs() <= (heights[b] - heights[d]).abs()
+                };
+                let triangles = if along_ac {
+                    [[a, b, c], [a, c, d]]
+                } else {
+                    [[a, b, d], [b, c, d]]
+                };
+                for triangle in triangles {
+                    if triangle.iter().all(|&vertex| finite(vertex)) {
+                        data.push_face(triangle);
+                    }
+                }
+            }
+        }
+        Mesh::from_data(data)
+    }
+}
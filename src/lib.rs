@@ -1,32 +1,75 @@
-#![feature(map_try_insert)]
 mod utils;
-use na::{Matrix4, Vector2, Vector3};
+use na::{Vector2, Vector3};
 use nalgebra as na;
 use pathfinding::prelude::dijkstra;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ops::RangeInclusive;
+#[allow(dead_code)]
 mod triangle;
 use triangle::Triangle;
 use wasm_bindgen::prelude::*;
+#[allow(non_snake_case)]
 mod triangleExtended;
-use triangleExtended::TriangleExtended;
+use triangleExtended::{Segment, TriangleExtended};
 mod line2;
 use line2::Line2;
 use std::cell::RefCell;
 use std::rc::{Rc, Weak};
+mod surface_point;
+pub use surface_point::SurfacePoint;
+mod exp_map;
+mod topology;
+pub use exp_map::{exp_map, log_map};
+mod geodesic_trace;
+pub use geodesic_trace::{trace_geodesic, GeodesicTrace};
+mod transport;
+pub use transport::{transport_along_path, ParallelTransport};
+mod disc;
+pub use disc::{geodesic_disc, GeodesicDisc};
+mod funnel;
+use funnel::{funnel, Portal};
+mod path_point;
+use path_point::PathPoint;
+mod path_mesh;
+pub use path_mesh::{get_path_mesh, PathMesh};
+mod options;
+pub use options::{PathOptions, PathStatus};
+mod diagnostics;
+pub use diagnostics::{set_debug, PathStats};
+mod bvh;
+mod mesh;
+pub use mesh::Mesh;
+mod weld;
+pub use weld::{weld_vertices, Welding};
+mod buffers;
+pub use buffers::MeshBuffers;
+mod query;
+use buffers::VertexIndex;
+pub use query::PathQuery;
+mod distance_matrix;
+pub use distance_matrix::{distance_matrix, DistanceMethod};
+mod distance_field;
+pub use distance_field::{distance_field, distance_field_from_points, DistanceField};
+mod costs;
+pub use costs::compute_weighted_path;
+mod blocked;
+mod polygons;
+use costs::Costs;
+pub use polygons::{triangulate_polygons, Triangulation};
+mod heightfield;
+mod path_result;
+pub use path_result::{compute_path, compute_path_with_options, PathResult};
 
 #[cfg(feature = "wee_alloc")]
 #[global_allocator]
 static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
 
-type PathGraph = HashMap<usize, Vec<(usize, i32)>>;
-type Wedge = Vec<Rc<RefCell<TriangleExtended>>>;
-const PI: f32 = std::f32::consts::PI - 0.2;
-
-#[wasm_bindgen]
-extern "C" {
-    #[wasm_bindgen(js_namespace = console, js_name = log)]
-    fn log_usize(a: usize);
-}
+type PathGraph = HashMap<usize, Vec<(usize, u64)>>;
+type Wedge = Vec<usize>;
+type UnfoldedFace = [(usize, Vector2<f32>); 3];
+const SCALE: f32 = 50000.0;
+///Stands in for an invalid input face.
+const NO_FACE: [usize; 3] = [usize::MAX; 3];
 
 #[wasm_bindgen]
 pub fn get_path(
@@ -39,113 +82,262 @@ pub fn get_path(
     data.get_path(start, end)
 }
 
+///State of a path being shortened one iteration at a time.
+struct Shortening {
+    path: Vec<PathPoint>,
+    iterations: usize,
+    ///Query start time, as returned by `options::now`.
+    started: f64,
+    ///Set once shortening has ended.
+    status: Option<PathStatus>,
+    query: Rc<QueryState>,
+}
+
+///State of one query, kept apart from `Data` so that queries on a shared mesh can interleave.
+#[derive(Debug, Default)]
+struct QueryState {
+    stats: RefCell<PathStats>,
+    ///Costs as they were when the query started.
+    costs: Option<Rc<Costs>>,
+    ///Faces blocked by the options of the query, or empty.
+    blocked: Vec<bool>,
+    ///Faces the query is restricted to, if any.
+    patch: Option<HashSet<usize>>,
+}
+
 struct Data {
     coordinates: Vec<Vector3<f32>>,
     path_graph: PathGraph,
     triangles: Vec<Rc<RefCell<TriangleExtended>>>,
+    faces: Vec<[usize; 3]>,
+    vertex_faces: Vec<Vec<usize>>,
+    ///Work done by the last query that gave a result.
+    stats: RefCell<PathStats>,
+    costs: RefCell<Option<Rc<Costs>>>,
 }
 
 impl Data {
+    ///Builds the mesh from flat coordinate and index arrays. Incomplete trailing chunks are
+    ///skipped. Faces with indices out of range or repeated are kept as unconnected placeholders,
+    ///so that the faces after them keep their ids.
     pub fn new(raw_coordinates: Vec<f32>, indices: Vec<usize>) -> Data {
-        console_error_panic_hook::set_once();
-        let mut coordinates: Vec<Vector3<f32>> = Vec::with_capacity(raw_coordinates.len());
-        let mut path_graph: PathGraph = HashMap::new();
-        let mut triangles = vec![];
-
-        for coord_chunk in raw_coordinates.chunks(3) {
-            coordinates
-                .push(Vector3::new(coord_chunk[0], coord_chunk[1], coord_chunk[2]) * 50000.0);
-        }
-
-        for chunk in indices.chunks(3) {
-            let indices = [chunk[0], chunk[1], chunk[2]];
-            let [a, b, c] = indices.map(|i| coordinates[i]);
-            triangles.push(Rc::new(RefCell::new(TriangleExtended {
-                indices: [chunk[0], chunk[1], chunk[2]],
-                triangle: Triangle::new(a, b, c),
-                triangle_unfolded: None,
-                sub_triangles: vec![],
-                parent: RefCell::new(Weak::new()),
-            })));
-
-            for (i, centeral_index) in chunk.iter().enumerate() {
-                let indices: [usize; 2] = if i == 0 {
-                    [1, 2]
-                } else if i == 1 {
-                    [0, 2]
-                } else {
-                    [0, 1]
-                };
-                match path_graph.get_mut(centeral_index) {
-                    Some(vec) => indices.iter().for_each(|index| {
-                        vec.push((
-                            chunk[*index],
-                            ((coordinates[*centeral_index] - coordinates[chunk[*index]])
-                                .magnitude()
-                                * 1000.0) as i32,
-                        ))
-                    }),
-                    None => {
-                        path_graph.insert(
-                            *centeral_index,
-                            indices
-                                .iter()
-                                .map(|index| {
-                                    (
-                                        chunk[*index],
-                                        ((coordinates[*centeral_index]
-                                            - coordinates[chunk[*index]])
-                                            .magnitude()
-                                            * 1000.0)
-                                            as i32,
-                                    )
-                                })
-                                .collect(),
-                        );
-                    }
-                };
-            }
+        Data::from_slices(&raw_coordinates, &indices)
+    }
+
+    ///Builds the mesh like `new` from borrowed buffers with any index type.
+    pub fn from_slices<T: VertexIndex>(raw_coordinates: &[f32], indices: &[T]) -> Data {
+        let coordinates = raw_coordinates
+            .chunks_exact(3)
+            .map(|x| Vector3::new(x[0], x[1], x[2]) * SCALE)
+            .collect();
+        let mut data = Data::with_vertices(coordinates, indices.len() / 3);
+        for chunk in indices.chunks_exact(3) {
+            data.push_face([chunk[0].index(), chunk[1].index(), chunk[2].index()]);
         }
+        data
+    }
+
+    ///Starts a mesh without faces on `coordinates`, in scaled units, with room for `faces` faces.
+    fn with_vertices(coordinates: Vec<Vector3<f32>>, faces: usize) -> Data {
+        utils::set_panic_hook();
         Data {
+            path_graph: HashMap::new(),
+            triangles: Vec::with_capacity(faces),
+            faces: Vec::with_capacity(faces),
+            vertex_faces: vec![vec![]; coordinates.len()],
             coordinates,
-            path_graph,
-            triangles,
+            stats: RefCell::new(PathStats::default()),
+            costs: RefCell::new(None),
         }
     }
 
-    pub fn get_path(mut self, start: usize, end: usize) -> Vec<f32> {
-        let mut dijkstra_path = self.compute_dijkstra(start, end).unwrap();
-        let mut finish = true;
-        let mut counter = 0;
-        loop {
-            for (index, path) in dijkstra_path.windows(3).enumerate() {
-                let new_path_segment = self.get_path_segment(path);
-                match new_path_segment {
-                    Some(val) => {
-                        dijkstra_path.splice(index..index + 3, val);
-
-                        let mut result = vec![];
-                        for index in dijkstra_path.iter() {
-                            let point = self.coordinates[*index];
-                            result.push(point.x);
-                            result.push(point.y);
-                            result.push(point.z);
-                        }
-                        finish = false;
-                        break;
-                    }
-                    None => finish = true,
+    ///Adds the face `indices` and its edges. Faces with indices out of range or repeated are kept
+    ///as unconnected placeholders.
+    fn push_face(&mut self, indices: [usize; 3]) {
+        let coordinates = &self.coordinates;
+        if indices.iter().any(|&i| i >= coordinates.len())
+            || indices[0] == indices[1]
+            || indices[1] == indices[2]
+            || indices[2] == indices[0]
+        {
+            let zero = Vector3::zeros();
+            self.faces.push(NO_FACE);
+            self.triangles
+                .push(Rc::new(RefCell::new(TriangleExtended::new(
+                    NO_FACE,
+                    Triangle::new(zero, zero, zero),
+                ))));
+            return;
+        }
+        for index in indices.iter() {
+            self.vertex_faces[*index].push(self.faces.len());
+        }
+        self.faces.push(indices);
+        let [a, b, c] = indices.map(|i| coordinates[i]);
+        self.triangles.push(Rc::new(RefCell::new(TriangleExtended {
+            indices,
+            triangle: Triangle::new(a, b, c),
+            triangle_unfolded: None,
+            sub_triangles: vec![],
+            parent: RefCell::new(Weak::new()),
+        })));
+
+        let chunk = indices;
+        for (i, centeral_index) in chunk.iter().enumerate() {
+            let indices: [usize; 2] = if i == 0 {
+                [1, 2]
+            } else if i == 1 {
+                [0, 2]
+            } else {
+                [0, 1]
+            };
+            let weight = |index: &usize| {
+                ((coordinates[*centeral_index] - coordinates[chunk[*index]]).magnitude() * 1000.0)
+                    as u64
+            };
+            self.path_graph
+                .entry(*centeral_index)
+                .or_default()
+                .extend(indices.iter().map(|index| (chunk[*index], weight(index))));
+        }
+    }
+
+    pub fn get_path(&self, start: usize, end: usize) -> Vec<f32> {
+        let path = self.shorten_path(start, end);
+        self.path_coordinates(&path)
+    }
+
+    ///Shortens the Dijkstra path between `start` and `end` until no wedge can be straightened.
+    fn shorten_path(&self, start: usize, end: usize) -> Vec<PathPoint> {
+        self.shorten_path_with_options(start, end, &PathOptions::default())
+            .path
+    }
+
+    ///Shortens the Dijkstra path like `shorten_path`, stopping early at the limits of `options`.
+    ///Every iteration shortens the path, so a truncated path is the best one found.
+    fn shorten_path_with_options(
+        &self,
+        start: usize,
+        end: usize,
+        options: &PathOptions,
+    ) -> Shortening {
+        let mut shortening = self.start_shortening(start, end, options);
+        while self.shorten_step(&mut shortening, options).is_none() {}
+        shortening
+    }
+
+    ///Computes the Dijkstra path to be shortened by `shorten_step`, avoiding faces blocked by
+    ///`options`.
+    fn start_shortening(&self, start: usize, end: usize, options: &PathOptions) -> Shortening {
+        let query = Rc::new(self.start_query(options));
+        let started = options::now();
+        let path = if query.has_varying_costs() {
+            self.weighted_dijkstra(&query, start, end)
+        } else {
+            let path = self.compute_dijkstra(&query, start, end);
+            path.map(|path| path.into_iter().map(PathPoint::Vertex).collect())
+        };
+        let dijkstra_time = options::now() - started;
+        query.record(|stats| stats.dijkstra_time += dijkstra_time);
+        self.shortening_from(query, path, started)
+    }
+
+    ///Starts a query with the costs set now and the faces blocked by `options`.
+    fn start_query(&self, options: &PathOptions) -> QueryState {
+        QueryState {
+            stats: RefCell::new(PathStats::default()),
+            costs: self.costs.borrow().clone(),
+            blocked: self.block(options),
+            patch: options.patch().map(|faces| faces.into_iter().collect()),
+        }
+    }
+
+    ///Starts shortening a path found by Dijkstra, or gives an ended `NoPath` query if there is
+    ///none.
+    fn shortening_from(
+        &self,
+        query: Rc<QueryState>,
+        path: Option<Vec<PathPoint>>,
+        started: f64,
+    ) -> Shortening {
+        let path = match path {
+            Some(path) => path,
+            None => {
+                return Shortening {
+                    path: vec![],
+                    iterations: 0,
+                    started,
+                    status: Some(PathStatus::NoPath),
+                    query,
                 }
             }
-            counter += 1;
-            log_usize(counter);
-            if finish == true {
+        };
+        let dijkstra_length = self.cumulative_lengths(&path).last().copied();
+        query.record(|stats| stats.dijkstra_length = dijkstra_length.unwrap_or(0.0));
+        log::debug!("dijkstra path of {} vertices", path.len());
+        Shortening {
+            path,
+            iterations: 0,
+            started,
+            status: None,
+            query,
+        }
+    }
+
+    ///Runs one shortening iteration unless a limit of `options` is reached. Returns the status
+    ///once shortening has ended.
+    fn shorten_step(
+        &self,
+        shortening: &mut Shortening,
+        options: &PathOptions,
+    ) -> Option<PathStatus> {
+        if shortening.status.is_some() {
+            return shortening.status;
+        }
+        if let Some(status) = options.check(shortening.iterations, shortening.started) {
+            log::debug!("shortening stopped: {:?}", status);
+            shortening.status = Some(status);
+            return shortening.status;
+        }
+        let (path, query) = (&mut shortening.path, &shortening.query);
+        let mut finish = true;
+        for index in 1..path.len().saturating_sub(1) {
+            let new_path_segment =
+                self.get_path_segment(query, path, index, options.angle_tolerance());
+            if let Some((range, val)) = new_path_segment {
+                path.splice(range, val);
+                finish = false;
                 break;
             }
         }
+        shortening.iterations += 1;
+        query.record(|stats| stats.iterations += 1);
+        log::debug!(
+            "shortening iteration {}, {} path points",
+            shortening.iterations,
+            path.len()
+        );
+        if options.has_progress() {
+            let length = self.cumulative_lengths(path).last().copied();
+            options.progress(shortening.iterations, length.unwrap_or(0.0));
+        }
+        if finish
+            && query.has_varying_costs()
+            && self.refract(query, path) > costs::REFRACTION_TOLERANCE
+        {
+            finish = false;
+        }
+        if finish {
+            log::debug!("shortening converged: {:?}", query.stats());
+            shortening.status = Some(PathStatus::Converged);
+        }
+        shortening.status
+    }
+
+    fn path_coordinates(&self, path: &[PathPoint]) -> Vec<f32> {
         let mut result = vec![];
-        for index in dijkstra_path.iter() {
-            let point = self.coordinates[*index] / 50000.0;
+        for point in path.iter() {
+            let point = self.point_position(point) / SCALE;
             result.push(point.x);
             result.push(point.y);
             result.push(point.z);
@@ -154,297 +346,385 @@ impl Data {
         result
     }
 
-    fn get_path_segment(&mut self, path: &[usize]) -> Option<Vec<usize>> {
-        let mut new_path = vec![];
-        match self.get_wedge(path) {
-            Some(mut wedge) => {
-                if wedge.len() == 2 {
-                    let a1 = wedge[0].borrow().indices[0];
-                    let b1 = wedge[0].borrow().indices[1];
-                    let c2 = wedge[1].borrow().indices[2];
-
-                    match wedge[0].borrow().parent.borrow().upgrade() {
-                        Some(parent1) => match wedge[1].borrow().parent.borrow().upgrade() {
-                            Some(parent2) => {
-                                let parent1_indices = parent1.borrow().indices;
-                                let parent2_indices = parent2.borrow().indices;
-                                if !parent1_indices.contains(&a1)
-                                    && !parent1_indices.contains(&b1)
-                                    && !parent2_indices.contains(&c2)
-                                {
-                                    if wedge[0].borrow().triangle.area() < 2.0
-                                        && wedge[1].borrow().triangle.area() < 2.0
-                                    {
-                                        new_path.push(b1);
-                                        new_path.push(wedge[0].borrow().indices[2]);
-                                        new_path.push(c2);
-
-                                        return Some(new_path);
-                                    }
-                                }
-                            }
-                            None => {}
-                        },
-                        None => {}
-                    }
-                }
-
-                Data::unfold_wedge(&mut wedge);
-
-                for index in self.cut(&wedge).iter() {
-                    new_path.push(*index);
-                }
-
-                if new_path.len() > 0 {
-                    if new_path.len() < 2 {
-                        new_path = vec![path[0], path[2]];
-                        match wedge[0].borrow().parent.borrow().upgrade() {
-                            Some(parent) => {
-                                parent.borrow_mut().sub_triangles.clear();
-                            }
-                            None => {}
-                        }
-                    }
-                    return Some(new_path);
-                }
-                None
+    ///Gets the distance from the path start to every path point, in input units. Segments are
+    ///summed in `f64`, so long paths of many short segments keep their precision.
+    fn cumulative_lengths(&self, path: &[PathPoint]) -> Vec<f64> {
+        let mut length = 0.0;
+        let mut result = Vec::with_capacity(path.len());
+        for (index, point) in path.iter().enumerate() {
+            if index > 0 {
+                let segment = self.point_position(point) - self.point_position(&path[index - 1]);
+                length += segment.cast::<f64>().magnitude() / SCALE as f64;
             }
-            None => None,
+            result.push(length);
         }
+        result
     }
 
-    fn compute_dijkstra(&self, start: usize, end: usize) -> Option<Vec<usize>> {
-        match dijkstra(
-            &start,
-            |index| -> Vec<(usize, i32)> { self.path_graph.get(index).unwrap().clone() },
-            |p| *p == end,
-        ) {
+    ///Straightens the path around vertex `path[index]` if one of its wedges is below π by more
+    ///than `tolerance`. The path between the neighbouring path vertices is replaced with the
+    ///shortest path across the faces it crosses and the wedge. Returns the replaced range and the
+    ///new path part.
+    fn get_path_segment(
+        &self,
+        query: &QueryState,
+        path: &[PathPoint],
+        index: usize,
+        tolerance: f32,
+    ) -> Option<(RangeInclusive<usize>, Vec<PathPoint>)> {
+        let wedge = self.get_wedge(query, path, index, tolerance)?;
+        let is_vertex = |i: &usize| matches!(path[*i], PathPoint::Vertex(_));
+        let first = (0..index).rev().find(is_vertex)?;
+        let last = (index + 1..path.len()).find(is_vertex)?;
+
+        let mut strip: Vec<usize> = path[first..=index]
+            .windows(2)
+            .filter_map(|pair| self.face_between(&pair[0], &pair[1]))
+            .collect();
+        strip.extend(wedge);
+        strip.extend(
+            path[index..=last]
+                .windows(2)
+                .filter_map(|pair| self.face_between(&pair[0], &pair[1])),
+        );
+        strip.dedup();
+
+        let unfolded = self.unfold_wedge(query, &strip)?;
+        let mut new_path = self.straighten(&unfolded, path[first], path[last])?;
+        // The straight path is the shortest, but not the cheapest across faces of different costs.
+        if query.has_varying_costs() {
+            self.refract(query, &mut new_path);
+        }
+        // Rounding must not let the path grow back and forth.
+        if self.path_length(query, &new_path) >= self.path_length(query, &path[first..=last]) {
+            return None;
+        }
+        Some((first..=last, new_path))
+    }
+
+    fn compute_dijkstra(&self, query: &QueryState, start: usize, end: usize) -> Option<Vec<usize>> {
+        if start >= self.coordinates.len() || end >= self.coordinates.len() {
+            return None;
+        }
+        match dijkstra(&start, |index| self.neighbors(query, index), |p| *p == end) {
             Some(val) => Some(val.0),
             None => None,
         }
     }
 
-    fn get_triangle_pair_by_edge(
-        &mut self,
-        a: usize,
-        b: usize,
-        neighbor: Option<Rc<RefCell<TriangleExtended>>>,
-    ) -> Vec<Rc<RefCell<TriangleExtended>>> {
-        let mut result = Vec::with_capacity(2);
-        for triangle in self.triangles.iter() {
-            let mut try_on_parent = true;
-            let sub_triangles_len = triangle.borrow().sub_triangles.len();
-            if sub_triangles_len > 0 {
-                let sub_triangles = &triangle.borrow().sub_triangles;
-                for sub_triangle in sub_triangles.iter() {
-                    let indices = sub_triangle.borrow().indices;
-                    let checker: Vec<(usize, &usize)> = indices
-                        .iter()
-                        .enumerate()
-                        .filter(|&(_, index)| *index == a || *index == b)
-                        .collect();
-                    if checker.len() == 2 {
-                        match &neighbor {
-                            Some(val) => {
-                                if Rc::ptr_eq(sub_triangle, val) {
-                                    try_on_parent = false;
-                                    continue;
-                                }
-                            }
-                            None => {}
-                        }
-                        let c = sub_triangle.borrow().indices[3 - checker[0].0 - checker[1].0];
-                        let indices = [a, b, c];
-                        sub_triangle.borrow_mut().indices = [a, b, c];
-                        sub_triangle.borrow_mut().triangle = self.get_triangle(indices);
-                        sub_triangle.borrow_mut().triangle_unfolded = None;
-                        result.push(Rc::clone(sub_triangle));
-                        try_on_parent = false;
-                    }
-                }
-            }
+    fn get_wedge(
+        &self,
+        query: &QueryState,
+        path: &[PathPoint],
+        index: usize,
+        tolerance: f32,
+    ) -> Option<Wedge> {
+        let wedges = self.get_wedges(query, path, index);
+        let wedge = wedges
+            .iter()
+            .flatten()
+            .reduce(|min, item| if min.1 <= item.1 { min } else { item })?;
+        if wedge.1 < std::f32::consts::PI - tolerance {
+            return Some(wedge.0.clone());
+        }
+        None
+    }
 
-            if try_on_parent == true {
-                let indices = triangle.borrow().indices;
+    ///Gets faces around vertex `path[index]` between the path segments before and after it, with
+    ///the sum of their angles at the vertex. The first wedge follows the face winding from the
+    ///previous path point and lies on the right of the path, the second one on the left. Wedges
+    ///that would cross the mesh boundary or blocked faces are `None`.
+    fn get_wedges(
+        &self,
+        query: &QueryState,
+        path: &[PathPoint],
+        index: usize,
+    ) -> [Option<(Wedge, f32)>; 2] {
+        let middle = match path[index] {
+            PathPoint::Vertex(vertex) => vertex,
+            PathPoint::Edge(..) => return [None, None],
+        };
+        let (fan, closed) = self.vertex_fan(middle);
+        let angles: Vec<f32> = fan
+            .iter()
+            .map(|&face| self.face_angle(face, middle))
+            .collect();
+        let total: f32 = angles.iter().sum();
+        let from = self.fan_position(&fan, &angles, middle, &path[index - 1]);
+        let to = self.fan_position(&fan, &angles, middle, &path[index + 1]);
+        match (from, to) {
+            (Some(from), Some(to)) => [true, false].map(|forward| {
+                let angle = if forward {
+                    to.1 - from.1
+                } else {
+                    from.1 - to.1
+                };
+                let angle = if angle > 0.0 {
+                    angle
+                } else if closed {
+                    angle + total
+                } else {
+                    return None;
+                };
+                let wedge = Data::sweep(&fan, closed, from, to, forward)?;
+                if wedge.iter().any(|&face| query.is_blocked(face)) {
+                    return None;
+                }
+                Some((wedge, angle))
+            }),
+            _ => [None, None],
+        }
+    }
 
-                let checker: Vec<(usize, &usize)> = indices
-                    .iter()
-                    .enumerate()
-                    .filter(|&(_, index)| *index == a || *index == b)
-                    .collect();
-
-                if checker.len() == 2 {
-                    match &neighbor {
-                        Some(val) => {
-                            if Rc::ptr_eq(triangle, val) {
-                                continue;
-                            }
-                        }
-                        None => {}
-                    }
-                    let c = triangle.borrow().indices[3 - checker[0].0 - checker[1].0];
-                    let indices = [a, b, c];
-                    triangle.borrow_mut().indices = [a, b, c];
-                    triangle.borrow_mut().triangle = self.get_triangle(indices);
-                    triangle.borrow_mut().triangle_unfolded = None;
-                    result.push(Rc::clone(triangle));
+    ///Locates path point `point` next to `middle` in its fan. Returns the fan position of the face
+    ///it lies in, or of the face starting at it, the angle from the first fan edge, and whether it
+    ///lies on a fan edge.
+    fn fan_position(
+        &self,
+        fan: &[usize],
+        angles: &[f32],
+        middle: usize,
+        point: &PathPoint,
+    ) -> Option<(usize, f32, bool)> {
+        let origin = self.coordinates[middle];
+        let mut angle = 0.0;
+        for (i, &face) in fan.iter().enumerate() {
+            let [_, a, b] = self.rotated_face(face, middle);
+            match point {
+                PathPoint::Vertex(vertex) if *vertex == a => return Some((i, angle, true)),
+                PathPoint::Edge(edge, _) if edge.contains(&a) && edge.contains(&b) => {
+                    let offset = (self.coordinates[a] - origin)
+                        .angle(&(self.point_position(point) - origin));
+                    return Some((i, angle + offset, false));
                 }
+                _ => {}
             }
+            angle += angles[i];
+        }
+        // The last edge of an open fan.
+        match (point, fan.last()) {
+            (PathPoint::Vertex(vertex), Some(&face))
+                if self.rotated_face(face, middle)[2] == *vertex =>
+            {
+                Some((fan.len(), angle, true))
+            }
+            _ => None,
         }
-        result
     }
 
-    fn get_wedge(&mut self, path: &[usize]) -> Option<Wedge> {
-        let mut start = path[0];
-        let middle = path[1];
-        let end = path[2];
-
-        let triangle_pair = self.get_triangle_pair_by_edge(middle, start, None);
-
-        let mut wedges: Vec<Vec<Rc<RefCell<TriangleExtended>>>> =
-            triangle_pair.into_iter().map(|x| vec![x]).collect();
-
-        for wedge in wedges.iter_mut() {
-            start = wedge.last().unwrap().borrow().indices[2];
-
-            while start != end {
-                let last_triangle = wedge.last().unwrap();
-                let pair =
-                    self.get_triangle_pair_by_edge(middle, start, Some(Rc::clone(last_triangle)));
-                let next_triangle = Rc::clone(&pair[0]);
-                start = pair[0].borrow().indices[2];
-                wedge.push(next_triangle);
+    ///Collects fan faces from fan position `from` to `to`, in the winding order if `forward`.
+    fn sweep(
+        fan: &[usize],
+        closed: bool,
+        from: (usize, f32, bool),
+        to: (usize, f32, bool),
+        forward: bool,
+    ) -> Option<Wedge> {
+        let count = fan.len();
+        let mut i = match (forward, from.2) {
+            (true, _) | (false, false) => from.0,
+            (false, true) if from.0 > 0 => from.0 - 1,
+            (false, true) if closed => count - 1,
+            _ => return None,
+        };
+        let mut wedge = vec![];
+        loop {
+            if i >= count || wedge.len() == count {
+                return None;
             }
-        }
-        let wedge = wedges
-            .iter()
-            .zip(wedges.iter().map(|wedge| {
-                wedge
-                    .iter()
-                    .map(|triangle| match triangle.borrow().triangle.angles() {
-                        Some(val) => val[0].abs(),
-                        None => std::f32::consts::PI,
-                    })
-                    .sum::<f32>()
-            }))
-            .reduce(|min, item| {
-                if min.1.abs() <= item.1.abs() {
-                    min
+            wedge.push(fan[i]);
+            let done = if to.2 {
+                if !forward {
+                    i == to.0
+                } else if closed {
+                    (i + 1) % count == to.0
                 } else {
-                    item
+                    i + 1 == to.0
                 }
-            })
-            .unwrap();
-        if wedge.1.abs() < PI {
-            return Some(wedge.0.clone());
+            } else {
+                i == to.0 && (wedge.len() > 1 || (to.1 > from.1) == forward)
+            };
+            if done {
+                return Some(wedge);
+            }
+            i = match forward {
+                true if i + 1 < count => i + 1,
+                true if closed => 0,
+                false if i > 0 => i - 1,
+                false if closed => count - 1,
+                _ => return None,
+            };
         }
-        None
     }
 
-    fn unfold_wedge(wedge: &mut Wedge) {
-        let mut basis_to = Matrix4::default();
-        basis_to.fill_with_identity();
-        wedge.iter_mut().for_each(|triangle| {
-            let basis_from = triangle.borrow().get_basis(
-                [triangle.borrow().indices[0], triangle.borrow().indices[1]],
-                triangle.borrow().triangle,
-            );
-            triangle.borrow_mut().transform(basis_from, basis_to);
-            basis_to = triangle.borrow().get_basis(
-                [triangle.borrow().indices[0], triangle.borrow().indices[2]],
-                triangle.borrow().triangle_unfolded.unwrap(),
-            )
-        });
+    ///Unfolds consecutive faces of `strip` into the plane, each next one over the edge it shares
+    ///with the previous one. Returns vertices and their plane positions for every face.
+    fn unfold_wedge(&self, query: &QueryState, strip: &[usize]) -> Option<Vec<UnfoldedFace>> {
+        query.record(|stats| stats.wedges_unfolded += 1);
+        let mut result: Vec<UnfoldedFace> = vec![];
+        for (i, &face) in strip.iter().enumerate() {
+            let shared: Vec<(usize, Vector2<f32>)> = match result.last() {
+                Some(previous) => previous
+                    .iter()
+                    .filter(|x| self.faces[face].contains(&x.0))
+                    .copied()
+                    .collect(),
+                None => {
+                    let [a, b, _] = self.faces[face];
+                    let length = (self.coordinates[b] - self.coordinates[a]).magnitude();
+                    vec![(a, Vector2::zeros()), (b, Vector2::new(length, 0.0))]
+                }
+            };
+            if shared.len() != 2 || (i > 0 && strip[i - 1] == face) {
+                return None;
+            }
+            let [a, b] = if self.rotated_face(face, shared[0].0)[1] == shared[1].0 {
+                [shared[0], shared[1]]
+            } else {
+                [shared[1], shared[0]]
+            };
+            let third = self.unfold_face(face, [a.0, b.0], [a.1, b.1]);
+            result.push([a, b, (self.rotated_face(face, a.0)[2], third)]);
+        }
+        Some(result)
     }
 
-    fn cut(&mut self, wedge: &Vec<Rc<RefCell<TriangleExtended>>>) -> Vec<usize> {
-        let start = wedge[0].borrow().triangle_unfolded.unwrap().b;
-        let end = wedge.last().unwrap().borrow().triangle_unfolded.unwrap().c;
-        let mut path = vec![(wedge[0].borrow().indices[1], wedge[0].borrow().triangle.b)];
-        let last_triangle = wedge.last().unwrap();
-
-        let path_line = Line2::new(v2_from_v3(start), v2_from_v3(end));
-
-        for i in 0..wedge.len() {
-            let mut triangle = Rc::clone(&wedge[i]);
-            let parent = triangle.borrow().parent.borrow_mut().upgrade();
-            if i != wedge.len() - 1 {
-                match &parent {
-                    Some(parent) => {
-                        let next_triangle = Rc::clone(&wedge[i + 1]);
-                        let next_triangle_parent =
-                            next_triangle.borrow().parent.borrow_mut().upgrade();
-                        match next_triangle_parent {
-                            Some(next_triangle_parent) => {
-                                if Rc::ptr_eq(&parent, &next_triangle_parent) {
-                                    continue;
-                                }
-                            }
-                            None => {}
-                        }
-                    }
-                    None => {}
+    ///Finds the shortest path from vertex `start` to vertex `end` across unfolded faces. The path
+    ///crosses edges between the faces at new edge points and bends at face vertices.
+    fn straighten(
+        &self,
+        unfolded: &[UnfoldedFace],
+        start: PathPoint,
+        end: PathPoint,
+    ) -> Option<Vec<PathPoint>> {
+        let (start, end) = match (start, end) {
+            (PathPoint::Vertex(start), PathPoint::Vertex(end)) => (start, end),
+            _ => return None,
+        };
+        let position =
+            |face: &UnfoldedFace, vertex: usize| face.iter().find(|x| x.0 == vertex).map(|x| x.1);
+
+        let mut portals = vec![Portal::point(start, position(unfolded.first()?, start)?)];
+        for pair in unfolded.windows(2) {
+            let shared: Vec<(usize, Vector2<f32>)> = pair[0]
+                .iter()
+                .filter(|x| pair[1].iter().any(|y| y.0 == x.0))
+                .copied()
+                .collect();
+            let third = pair[0].iter().find(|x| !shared.contains(x))?.1;
+            let [a, b] = [shared[0], shared[1]];
+            portals.push(if (a.1 - third).perp(&(b.1 - third)) > 0.0 {
+                Portal { left: b, right: a }
+            } else {
+                Portal { left: a, right: b }
+            });
+        }
+        portals.push(Portal::point(end, position(unfolded.last()?, end)?));
+
+        let corners = funnel(&portals);
+        let corner_position = |(i, vertex): (usize, usize)| {
+            if portals[i].left.0 == vertex {
+                portals[i].left.1
+            } else {
+                portals[i].right.1
+            }
+        };
+        let mut result = vec![PathPoint::Vertex(start)];
+        for pair in corners.windows(2) {
+            let (from, to) = (corner_position(pair[0]), corner_position(pair[1]));
+            for portal in portals.get(pair[0].0 + 1..pair[1].0).unwrap_or(&[]).iter() {
+                let ends = [portal.left.0, portal.right.0];
+                if ends.contains(&pair[0].1) || ends.contains(&pair[1].1) {
+                    continue;
                 }
+                let t = Line2::new(portal.left.1, portal.right.1)
+                    .intersect_lines(&Line2::new(from, to))
+                    .map_or(0.5, |crossing| crossing.t);
+                // Crossings next to a vertex would only leave slivers.
+                result.push(if t < 0.0001 {
+                    PathPoint::Vertex(portal.left.0)
+                } else if t > 0.9999 {
+                    PathPoint::Vertex(portal.right.0)
+                } else {
+                    PathPoint::Edge(ends, t)
+                });
             }
+            result.push(PathPoint::Vertex(pair[1].1));
+        }
+        result.dedup();
+        Some(result)
+    }
 
-            let edge = Line2::new(
-                v2_from_v3(triangle.borrow().triangle_unfolded.unwrap().a),
-                v2_from_v3(triangle.borrow().triangle_unfolded.unwrap().c),
-            );
-
-            match path_line.intersect(&edge) {
-                Some(intersection) => {
-                    let a = self.coordinates[triangle.borrow().indices[0]];
-                    let b = self.coordinates[triangle.borrow().indices[2]];
-                    let point = a + (b - a).normalize() * ((intersection - edge.start).magnitude());
-
-                    self.coordinates.push(point);
-                    let index = self.coordinates.len() - 1;
-
-                    match parent {
-                        Some(parent) => {
-                            triangle = parent;
-                        }
-                        None => {}
-                    }
-
-                    triangle.borrow_mut().sub_triangles.clear();
-                    let sub_triangles =
-                        triangle.borrow().cut(*path.last().unwrap(), (index, point));
-                    if sub_triangles.len() > 0 {
-                        for sub_triangle in sub_triangles {
-                            *sub_triangle.parent.borrow_mut() = Rc::downgrade(&triangle);
-                            triangle.borrow_mut().add_child(sub_triangle);
-                        }
-                        path.push((index, point));
-                    }
-                }
-                None => {
-                    if Rc::ptr_eq(&triangle, last_triangle) {
-                        match parent {
-                            Some(parent) => {
-                                triangle = parent;
-                            }
-                            None => {}
-                        }
-                        let index = last_triangle.borrow().indices[2];
-                        let point = last_triangle.borrow().triangle.c;
-                        triangle.borrow_mut().sub_triangles.clear();
-                        let sub_triangles =
-                            triangle.borrow().cut(*path.last().unwrap(), (index, point));
-                        if sub_triangles.len() > 0 {
-                            for sub_triangle in sub_triangles {
-                                *sub_triangle.parent.borrow_mut() = Rc::downgrade(&triangle);
-                                triangle.borrow_mut().add_child(sub_triangle);
-                            }
-                            path.push((index, point));
-                        }
-                    }
+    ///Gets the length of the path in scaled units, weighted by face costs if set.
+    fn path_length(&self, query: &QueryState, path: &[PathPoint]) -> f32 {
+        path.windows(2)
+            .map(|pair| {
+                let length =
+                    (self.point_position(&pair[1]) - self.point_position(&pair[0])).magnitude();
+                length * self.segment_cost(query, &pair[0], &pair[1])
+            })
+            .sum()
+    }
+
+    ///Splits input triangles along `path`, so that it runs along edges of their sub triangles.
+    ///Edge points of the path are added to `coordinates`. Returns indices of path points in
+    ///`coordinates`.
+    fn cut(&mut self, query: &QueryState, path: &[PathPoint]) -> Vec<usize> {
+        let mut points = vec![];
+        for point in path.iter() {
+            let position = self.point_position(point);
+            let index = match point {
+                PathPoint::Vertex(vertex) => *vertex,
+                PathPoint::Edge(..) => {
+                    self.coordinates.push(position);
+                    self.coordinates.len() - 1
                 }
+            };
+            points.push((index, position));
+        }
+
+        // A face can hold several segments, so all of them are cut at once.
+        let mut segments: BTreeMap<usize, Vec<Segment>> = BTreeMap::new();
+        for (pair, segment) in path.windows(2).zip(points.windows(2)) {
+            if let Some(face) = self.face_between(&pair[0], &pair[1]) {
+                segments
+                    .entry(face)
+                    .or_default()
+                    .push([segment[0], segment[1]]);
             }
         }
+        for (face, segments) in segments.iter() {
+            self.split(query, *face, segments);
+        }
+        points.iter().map(|x| x.0).collect()
+    }
+
+    ///Cuts input face `face` along `segments` and puts its pieces under it.
+    fn split(&self, query: &QueryState, face: usize, segments: &[Segment]) {
+        let triangle = &self.triangles[face];
+        let sub_triangles = triangle.borrow().cut(segments);
+        if sub_triangles.is_empty() {
+            return;
+        }
+        let count = sub_triangles.len();
+        query.record(|stats| {
+            stats.cuts += segments.len();
+            stats.sub_triangles += count;
+        });
+        log::trace!("cut face {:?} into {}", triangle.borrow().indices, count);
+        for sub_triangle in sub_triangles {
+            *sub_triangle.parent.borrow_mut() = Rc::downgrade(triangle);
+            triangle.borrow_mut().add_child(sub_triangle);
+        }
+    }
 
-        path.iter().map(|x| x.0).collect()
+    ///Gets the vertices of face `face`, or `None` if it is out of range or a placeholder of an
+    ///invalid input face.
+    pub(crate) fn face(&self, face: usize) -> Option<[usize; 3]> {
+        self.faces
+            .get(face)
+            .copied()
+            .filter(|face| *face != NO_FACE)
     }
 
     fn get_triangle(&self, indices: [usize; 3]) -> Triangle {
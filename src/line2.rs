@@ -1,50 +1,113 @@
 use na::Vector2;
 use nalgebra as na;
+use robust::{orient2d, Coord};
 
+///Segment in the plane from `start` to `end`.
+#[derive(Debug, Copy, Clone)]
 pub struct Line2 {
     pub start: Vector2<f32>,
     pub end: Vector2<f32>,
 }
 
+///Crossing of two lines: the point and its parameters on both lines, 0 at the start and 1 at the
+///end.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Intersection {
+    pub point: Vector2<f32>,
+    pub t: f32,
+    pub u: f32,
+}
+
 impl Line2 {
     pub fn new(start: Vector2<f32>, end: Vector2<f32>) -> Line2 {
         Line2 { start, end }
     }
-    fn get_dir(&self) -> Vector2<f32> {
+
+    pub fn get_dir(&self) -> Vector2<f32> {
         self.end - self.start
     }
 
-    fn length(&self) -> f32 {
-        self.get_dir().magnitude()
+    ///Gets twice the signed area of the triangle from the line to `point`: positive if it lies on
+    ///the left, negative on the right. The sign is exact, using adaptive precision predicates.
+    pub fn side(&self, point: Vector2<f32>) -> f64 {
+        orient2d(coord(self.start), coord(self.end), coord(point))
     }
 
-    fn get_eq(&self) -> (f32, f32) {
-        let v = self.get_dir();
-        let k = v.y / v.x;
-        let b = self.start.y - k * self.start.x;
-        (k, b)
+    ///Intersects the lines through both segments. Returns `None` if they are parallel.
+    pub fn intersect_lines(&self, line: &Line2) -> Option<Intersection> {
+        let (start, end) = (self.side(line.start), self.side(line.end));
+        let (first, second) = (line.side(self.start), line.side(self.end));
+        if start == end || first == second {
+            return None;
+        }
+        let t = (first / (first - second)) as f32;
+        Some(Intersection {
+            point: self.start + self.get_dir() * t,
+            t,
+            u: (start / (start - end)) as f32,
+        })
     }
 
-    fn is_collinear(&self, line: &Line2) -> bool {
-        let a = self.get_dir();
-        let b = line.get_dir();
-        (a.x * b.y - a.y * b.x).abs() < std::f32::EPSILON
-    }
-    pub fn intersect(&self, line: &Line2) -> Option<Vector2<f32>> {
-        if self.is_collinear(line) {
-            return None;
+    ///Intersects the segments. Returns `None` if they don't cross or touch, or overlap.
+    pub fn intersect(&self, line: &Line2) -> Option<Intersection> {
+        let crosses = |a: f64, b: f64| (a <= 0.0 && b >= 0.0) || (a >= 0.0 && b <= 0.0);
+        if crosses(self.side(line.start), self.side(line.end))
+            && crosses(line.side(self.start), line.side(self.end))
+        {
+            self.intersect_lines(line)
         } else {
-            let dir = self.get_dir();
-            let eq1 = self.get_eq();
-            let eq2 = line.get_eq();
-            let x = (eq2.1 - eq1.1) / (eq1.0 - eq2.0);
-            let y = eq1.0 * x + eq1.1;
-            let intersect = Vector2::new(x, y);
-            let sign_length = (intersect - self.start).dot(&dir.normalize());
-            if sign_length < dir.magnitude() - 0.01 && sign_length > 0.01 {
-                return Some(intersect);
+            None
+        }
+    }
+}
+
+fn coord(point: Vector2<f32>) -> Coord<f64> {
+    Coord {
+        x: point.x as f64,
+        y: point.y as f64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn point() -> impl Strategy<Value = Vector2<f32>> {
+        (-10.0f32..10.0, -10.0f32..10.0).prop_map(|(x, y)| Vector2::new(x, y))
+    }
+
+    fn line() -> impl Strategy<Value = Line2> {
+        // Every third line is vertical or horizontal.
+        (point(), point(), 0usize..3).prop_map(|(start, end, kind)| match kind {
+            0 => Line2::new(start, Vector2::new(start.x, end.y)),
+            1 => Line2::new(start, Vector2::new(end.x, start.y)),
+            _ => Line2::new(start, end),
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn intersection_lies_on_both_segments(first in line(), second in line()) {
+            if let Some(crossing) = first.intersect(&second) {
+                prop_assert!((0.0..=1.0).contains(&crossing.t));
+                prop_assert!((0.0..=1.0).contains(&crossing.u));
+                let on_second = second.start + second.get_dir() * crossing.u;
+                prop_assert!((crossing.point - on_second).magnitude() < 1e-3);
             }
-            return None;
+        }
+
+        #[test]
+        fn crossing_segments_intersect(first in line(), t in 0.01f32..0.99, u in 0.01f32..0.99, angle in 0.1f32..3.0) {
+            // A segment through the point at `t` on `first`, at `u` along itself.
+            let point = first.start + first.get_dir() * t;
+            let direction = na::Rotation2::new(angle) * first.get_dir();
+            let second = Line2::new(point - direction * u, point + direction * (1.0 - u));
+            prop_assume!(first.get_dir().magnitude() > 0.1);
+            let crossing = first.intersect(&second);
+            prop_assert!(crossing.is_some());
+            let crossing = crossing.unwrap();
+            prop_assert!((crossing.t - t).abs() < 1e-3 && (crossing.u - u).abs() < 1e-3);
         }
     }
 }
@@ -0,0 +1,216 @@
+use crate::bvh::Bvh;
+use crate::{Data, PathOptions, PathResult, PathStats, SurfacePoint, Welding, SCALE};
+use na::Vector3;
+use nalgebra as na;
+use std::cell::OnceCell;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+
+///Mesh kept between queries, so that it's preprocessed only once.
+#[wasm_bindgen]
+pub struct Mesh {
+    ///Shared with running `PathQuery`s.
+    pub(crate) data: Rc<Data>,
+    ///Built by the first spatial query.
+    bvh: OnceCell<Bvh>,
+    ///Vertex kept for every input vertex, if welded.
+    pub(crate) welded: Option<Vec<usize>>,
+    ///Input polygon of every face, if built from polygons.
+    pub(crate) polygons: Option<Vec<usize>>,
+}
+
+#[wasm_bindgen]
+impl Mesh {
+    #[wasm_bindgen(constructor)]
+    pub fn new(raw_coordinates: Vec<f32>, indices: Vec<usize>) -> Mesh {
+        Mesh::from_data(Data::new(raw_coordinates, indices))
+    }
+
+    ///Builds the mesh with vertices closer than `tolerance` merged like `weld_vertices`, so that
+    ///paths cross seams of duplicated vertices. Queries take and return input vertex indices.
+    pub fn with_welding(raw_coordinates: Vec<f32>, indices: Vec<usize>, tolerance: f32) -> Mesh {
+        let welding = Welding::new(&raw_coordinates, &indices, tolerance);
+        let mut mesh = Mesh::from_data(Data::new(raw_coordinates, welding.indices()));
+        mesh.welded = Some(welding.vertices());
+        mesh
+    }
+
+    ///Computes the path between `start` and `end` like `get_path`.
+    pub fn get_path(&self, start: usize, end: usize) -> Vec<f32> {
+        self.data.get_path(self.vertex(start), self.vertex(end))
+    }
+
+    ///Computes the path between `start` and `end` like `compute_path_with_options`.
+    pub fn compute_path(&self, start: usize, end: usize, options: &PathOptions) -> PathResult {
+        let (start, end) = (self.vertex(start), self.vertex(end));
+        let options = self.face_options(options);
+        let shortening = self.data.shorten_path_with_options(start, end, &options);
+        self.data.path_result(&shortening, &options)
+    }
+
+    ///Gets the first surface point hit by the ray from `origin` along `direction`, e.g. a camera
+    ///ray through a clicked pixel. Gives nothing for vectors of fewer than 3 values.
+    pub fn pick(&self, origin: Vec<f32>, direction: Vec<f32>) -> Option<SurfacePoint> {
+        if origin.len() < 3 || direction.len() < 3 {
+            return None;
+        }
+        let origin = Vector3::from_column_slice(&origin) * SCALE;
+        let direction = Vector3::from_column_slice(&direction) * SCALE;
+        let (face, t) = self.bvh().ray_cast(origin, direction)?;
+        Some(self.surface_point(face, origin + direction * t))
+    }
+
+    ///Gets the surface point closest to `point`. Gives nothing for fewer than 3 values.
+    pub fn closest_point(&self, point: Vec<f32>) -> Option<SurfacePoint> {
+        if point.len() < 3 {
+            return None;
+        }
+        let point = Vector3::from_column_slice(&point) * SCALE;
+        let (face, closest) = self.bvh().nearest(point)?;
+        Some(self.surface_point(face, closest))
+    }
+
+    ///Serializes the mesh, e.g. to transfer its buffer to a worker and rebuild it with
+    ///`from_bytes`. Placeholders of invalid faces are kept, so face ids stay the same, and so are
+    ///the costs set on the mesh.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let welded = self.welded.as_deref().unwrap_or(&[]);
+        let polygons = self.polygons.as_deref().unwrap_or(&[]);
+        let (face_costs, vertex_costs) = self.data.cost_buffers();
+        let mut bytes = vec![];
+        for count in [
+            self.data.coordinates.len(),
+            self.data.faces.len(),
+            welded.len(),
+            polygons.len(),
+            face_costs.len(),
+            vertex_costs.len(),
+        ] {
+            bytes.extend_from_slice(&(count as u32).to_le_bytes());
+        }
+        for coordinate in self.data.coordinates.iter() {
+            for x in (coordinate / SCALE).iter() {
+                bytes.extend_from_slice(&x.to_le_bytes());
+            }
+        }
+        let indices = self.data.faces.iter().flatten();
+        let indices = indices.chain(welded.iter()).chain(polygons.iter());
+        for index in indices {
+            bytes.extend_from_slice(&(*index as u32).to_le_bytes());
+        }
+        for cost in face_costs.iter().chain(vertex_costs.iter()) {
+            bytes.extend_from_slice(&cost.to_le_bytes());
+        }
+        bytes
+    }
+
+    ///Rebuilds a mesh serialized by `to_bytes`. A truncated buffer, or one with counts too large
+    ///for the platform, gives an empty mesh.
+    pub fn from_bytes(bytes: &[u8]) -> Mesh {
+        let words: Vec<[u8; 4]> = bytes
+            .chunks_exact(4)
+            .map(|word| [word[0], word[1], word[2], word[3]])
+            .collect();
+        let counts: Vec<usize> = words
+            .iter()
+            .take(6)
+            .map(|word| u32::from_le_bytes(*word) as usize)
+            .collect();
+        // Counts near `u32::MAX` overflow `usize` on wasm32.
+        let sizes = match counts[..] {
+            [vertices, faces, welded, polygons, face_costs, vertex_costs] => vertices
+                .checked_mul(3)
+                .zip(faces.checked_mul(3))
+                .map(|(vertices, faces)| {
+                    [vertices, faces, welded, polygons, face_costs, vertex_costs]
+                }),
+            _ => Some([0; 6]),
+        };
+        let total = sizes.and_then(|sizes| {
+            sizes
+                .iter()
+                .try_fold(6usize, |total, &size| total.checked_add(size))
+        });
+        let sizes = match (sizes, total) {
+            (Some(sizes), Some(total)) if words.len() >= total => sizes,
+            _ => return Mesh::from_data(Data::from_slices::<u32>(&[], &[])),
+        };
+        let (coordinates, rest) = words[6..].split_at(sizes[0]);
+        let (indices, rest) = rest.split_at(sizes[1]);
+        let (welded, rest) = rest.split_at(sizes[2]);
+        let (polygons, rest) = rest.split_at(sizes[3]);
+        let (face_costs, rest) = rest.split_at(sizes[4]);
+        let floats = |words: &[[u8; 4]]| -> Vec<f32> {
+            words.iter().map(|x| f32::from_le_bytes(*x)).collect()
+        };
+        let indices: Vec<u32> = indices.iter().map(|x| u32::from_le_bytes(*x)).collect();
+        let mut mesh = Mesh::from_data(Data::from_slices(&floats(coordinates), &indices));
+        if sizes[2] > 0 {
+            mesh.welded = Some(
+                welded
+                    .iter()
+                    .map(|x| u32::from_le_bytes(*x) as usize)
+                    .collect(),
+            );
+        }
+        if sizes[3] > 0 {
+            let polygons = polygons.iter();
+            mesh.polygons = Some(polygons.map(|x| u32::from_le_bytes(*x) as usize).collect());
+        }
+        mesh.data
+            .restore_costs(&floats(face_costs), &floats(&rest[..sizes[5]]));
+        mesh
+    }
+
+    ///Work done by the last query that gave a result. Every `PathResult` has the stats of its own
+    ///query.
+    #[wasm_bindgen(getter)]
+    pub fn stats(&self) -> PathStats {
+        self.data.stats()
+    }
+}
+
+impl Mesh {
+    pub(crate) fn from_data(data: Data) -> Mesh {
+        Mesh {
+            data: Rc::new(data),
+            bvh: OnceCell::new(),
+            welded: None,
+            polygons: None,
+        }
+    }
+
+    ///Gets the vertex that stands for input vertex `vertex` after welding.
+    pub(crate) fn vertex(&self, vertex: usize) -> usize {
+        self.welded
+            .as_ref()
+            .and_then(|welded| welded.get(vertex).copied())
+            .unwrap_or(vertex)
+    }
+
+    fn surface_point(&self, face: usize, position: Vector3<f32>) -> SurfacePoint {
+        let mut point = self.data.surface_point(face, position);
+        point.face = self.polygon(face);
+        point
+    }
+
+    fn bvh(&self) -> &Bvh {
+        self.bvh.get_or_init(|| {
+            let faces = (0..self.data.faces.len()).filter_map(|face| {
+                let indices = self.data.face(face)?;
+                Some((face, self.data.get_triangle(indices)))
+            });
+            Bvh::new(faces.collect())
+        })
+    }
+}
+
+impl Data {
+    ///Gets the surface point at `position`, in scaled units, on face `face`.
+    fn surface_point(&self, face: usize, position: Vector3<f32>) -> SurfacePoint {
+        let barycentric = self
+            .get_triangle(self.faces[face])
+            .cartesian_to_barycentric(&position);
+        SurfacePoint::new(face, barycentric, position / SCALE)
+    }
+}
@@ -0,0 +1,186 @@
+use wasm_bindgen::prelude::*;
+
+const ANGLE_TOLERANCE: f32 = 0.2;
+
+///Limits of path shortening. Without limits shortening runs until no wedge can be straightened.
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct PathOptions {
+    max_iterations: usize,
+    angle_tolerance: f32,
+    time_limit: Option<f64>,
+    cancel: Option<js_sys::Function>,
+    progress: Option<js_sys::Function>,
+    blocked_faces: Vec<usize>,
+    blocked_vertices: Vec<usize>,
+    patch: Option<Vec<usize>>,
+}
+
+#[wasm_bindgen]
+impl PathOptions {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> PathOptions {
+        PathOptions {
+            max_iterations: usize::MAX,
+            angle_tolerance: ANGLE_TOLERANCE,
+            time_limit: None,
+            cancel: None,
+            progress: None,
+            blocked_faces: vec![],
+            blocked_vertices: vec![],
+            patch: None,
+        }
+    }
+
+    ///Maximum number of shortening iterations.
+    #[wasm_bindgen(getter)]
+    pub fn max_iterations(&self) -> usize {
+        self.max_iterations
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_max_iterations(&mut self, max_iterations: usize) {
+        self.max_iterations = max_iterations;
+    }
+
+    ///Angle a vertex wedge may miss π by and still be left unstraightened. Smaller values give
+    ///more accurate paths in more iterations.
+    #[wasm_bindgen(getter)]
+    pub fn angle_tolerance(&self) -> f32 {
+        self.angle_tolerance
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_angle_tolerance(&mut self, angle_tolerance: f32) {
+        self.angle_tolerance = angle_tolerance;
+    }
+
+    ///Wall-clock budget of the query in milliseconds.
+    #[wasm_bindgen(getter)]
+    pub fn time_limit(&self) -> Option<f64> {
+        self.time_limit
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_time_limit(&mut self, time_limit: Option<f64>) {
+        self.time_limit = time_limit;
+    }
+
+    ///Callback called between iterations. Shortening stops once it returns a truthy value or
+    ///throws.
+    #[wasm_bindgen(setter)]
+    pub fn set_cancel(&mut self, cancel: Option<js_sys::Function>) {
+        self.cancel = cancel;
+    }
+
+    ///Callback called after every iteration with the number of iterations done and the current
+    ///path length.
+    #[wasm_bindgen(setter)]
+    pub fn set_progress(&mut self, progress: Option<js_sys::Function>) {
+        self.progress = progress;
+    }
+
+    ///Faces paths must not cross or unfold into. They may run along their boundary.
+    #[wasm_bindgen(getter)]
+    pub fn blocked_faces(&self) -> Vec<usize> {
+        self.blocked_faces.clone()
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_blocked_faces(&mut self, faces: Vec<usize>) {
+        self.blocked_faces = faces;
+    }
+
+    ///Vertices paths must not pass. All faces around them are blocked.
+    #[wasm_bindgen(getter)]
+    pub fn blocked_vertices(&self) -> Vec<usize> {
+        self.blocked_vertices.clone()
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_blocked_vertices(&mut self, vertices: Vec<usize>) {
+        self.blocked_vertices = vertices;
+    }
+
+    ///Faces paths must stay on, or `undefined` for the whole mesh. The border of the patch acts
+    ///like the mesh boundary, and Dijkstra searches don't leave it, so small patches are fast.
+    #[wasm_bindgen(getter)]
+    pub fn patch(&self) -> Option<Vec<usize>> {
+        self.patch.clone()
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_patch(&mut self, faces: Option<Vec<usize>>) {
+        self.patch = faces;
+    }
+}
+
+impl Default for PathOptions {
+    fn default() -> Self {
+        PathOptions::new()
+    }
+}
+
+///How path shortening ended. Anything but `Converged` means the path was truncated and may still
+///be shortened.
+#[wasm_bindgen]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PathStatus {
+    Converged,
+    IterationLimit,
+    TimeLimit,
+    Cancelled,
+    ///Start and end are not connected or not in the mesh, so the path is empty.
+    NoPath,
+}
+
+impl PathOptions {
+    ///Checks the limits after `iterations` iterations of a query started at `started`, as
+    ///returned by `now`.
+    pub(crate) fn check(&self, iterations: usize, started: f64) -> Option<PathStatus> {
+        if iterations >= self.max_iterations {
+            return Some(PathStatus::IterationLimit);
+        }
+        if let Some(time_limit) = self.time_limit {
+            if now() - started >= time_limit {
+                return Some(PathStatus::TimeLimit);
+            }
+        }
+        if let Some(cancel) = &self.cancel {
+            if cancel.call0(&JsValue::NULL).map_or(true, |x| x.is_truthy()) {
+                return Some(PathStatus::Cancelled);
+            }
+        }
+        None
+    }
+
+    pub(crate) fn has_progress(&self) -> bool {
+        self.progress.is_some()
+    }
+
+    ///Reports progress to the callback. Errors it throws are ignored.
+    pub(crate) fn progress(&self, iterations: usize, length: f64) {
+        if let Some(progress) = &self.progress {
+            let _ = progress.call2(
+                &JsValue::NULL,
+                &JsValue::from(iterations as f64),
+                &JsValue::from(length),
+            );
+        }
+    }
+}
+
+///Gets current time in milliseconds.
+pub(crate) fn now() -> f64 {
+    #[cfg(target_arch = "wasm32")]
+    {
+        js_sys::Date::now()
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use std::time::{SystemTime, UNIX_EPOCH};
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0.0, |x| x.as_secs_f64() * 1000.0)
+    }
+}
@@ -0,0 +1,97 @@
+use crate::{Data, PathOptions, PathStats, SCALE};
+use wasm_bindgen::prelude::*;
+
+///Triangle mesh refined along a path, so that the path runs along its edges.
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct PathMesh {
+    positions: Vec<f32>,
+    indices: Vec<usize>,
+    path: Vec<usize>,
+    stats: PathStats,
+}
+
+#[wasm_bindgen]
+impl PathMesh {
+    ///Vertex positions. Input vertices keep their indices, path vertices are appended.
+    #[wasm_bindgen(getter)]
+    pub fn positions(&self) -> Vec<f32> {
+        self.positions.clone()
+    }
+
+    ///Face indices, three per triangle, wound like the input faces.
+    #[wasm_bindgen(getter)]
+    pub fn indices(&self) -> Vec<usize> {
+        self.indices.clone()
+    }
+
+    ///Vertex indices of the path, from start to end.
+    #[wasm_bindgen(getter)]
+    pub fn path(&self) -> Vec<usize> {
+        self.path.clone()
+    }
+
+    ///Work done by the query, including the cuts.
+    #[wasm_bindgen(getter)]
+    pub fn stats(&self) -> PathStats {
+        self.stats
+    }
+}
+
+///Computes the path between `start` and `end` like `get_path` and returns the mesh cut along
+///it.
+#[wasm_bindgen]
+pub fn get_path_mesh(
+    start: usize,
+    end: usize,
+    raw_coordinates: Vec<f32>,
+    indices: Vec<usize>,
+) -> PathMesh {
+    let mut data = Data::new(raw_coordinates, indices);
+    let shortening = data.shorten_path_with_options(start, end, &PathOptions::default());
+    let path = data.cut(&shortening.query, &shortening.path);
+    data.path_mesh(&path, data.finish(&shortening.query))
+}
+
+impl Data {
+    ///Collects the triangles `cut` left along `path` into a mesh. Sub triangles take the winding
+    ///of their input face.
+    pub fn path_mesh(&self, path: &[usize], stats: PathStats) -> PathMesh {
+        let mut indices = vec![];
+        for (face, triangle) in self.triangles.iter().enumerate() {
+            let sub_triangles = &triangle.borrow().sub_triangles;
+            if sub_triangles.is_empty() && self.face(face).is_some() {
+                indices.extend_from_slice(&self.faces[face]);
+            }
+            for sub_triangle in sub_triangles.iter() {
+                indices.extend_from_slice(&self.oriented(face, sub_triangle.borrow().indices));
+            }
+        }
+
+        let mut positions = vec![];
+        for point in self.coordinates.iter() {
+            let point = point / SCALE;
+            positions.push(point.x);
+            positions.push(point.y);
+            positions.push(point.z);
+        }
+        PathMesh {
+            positions,
+            indices,
+            path: path.to_vec(),
+            stats,
+        }
+    }
+
+    ///Orders `indices` like the winding of input face `face`.
+    fn oriented(&self, face: usize, indices: [usize; 3]) -> [usize; 3] {
+        let face_normal = self.get_triangle(self.faces[face]).normal();
+        let normal = self.get_triangle(indices).normal();
+        match (face_normal, normal) {
+            (Some(face_normal), Some(normal)) if normal.dot(&face_normal) < 0.0 => {
+                [indices[0], indices[2], indices[1]]
+            }
+            _ => indices,
+        }
+    }
+}
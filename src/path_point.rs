@@ -0,0 +1,58 @@
+use crate::Data;
+use na::Vector3;
+use nalgebra as na;
+
+///Point of a path: an input vertex or a point on an input edge.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) enum PathPoint {
+    Vertex(usize),
+    ///Point `edge[0] + (edge[1] - edge[0]) * t`, with `t` between 0 and 1.
+    Edge([usize; 2], f32),
+}
+
+impl PathPoint {
+    ///Gets input vertices the point lies on.
+    pub fn vertices(&self) -> Vec<usize> {
+        match self {
+            PathPoint::Vertex(vertex) => vec![*vertex],
+            PathPoint::Edge(edge, _) => edge.to_vec(),
+        }
+    }
+}
+
+impl Data {
+    pub(crate) fn point_position(&self, point: &PathPoint) -> Vector3<f32> {
+        match point {
+            PathPoint::Vertex(vertex) => self.coordinates[*vertex],
+            PathPoint::Edge([a, b], t) => {
+                self.coordinates[*a] + (self.coordinates[*b] - self.coordinates[*a]) * *t
+            }
+        }
+    }
+
+    ///Gets the input face that contains the path segment between `a` and `b`. Returns `None` if
+    ///the segment runs along an input edge.
+    pub(crate) fn face_between(&self, a: &PathPoint, b: &PathPoint) -> Option<usize> {
+        if let (PathPoint::Vertex(_), PathPoint::Vertex(_)) = (a, b) {
+            return None;
+        }
+        let vertices = [a.vertices(), b.vertices()].concat();
+        self.vertex_faces[vertices[0]]
+            .iter()
+            .find(|&&face| {
+                vertices
+                    .iter()
+                    .all(|vertex| self.faces[face].contains(vertex))
+            })
+            .copied()
+    }
+
+    ///Gets faces on both sides of the input edge between `a` and `b`.
+    pub(crate) fn edge_faces(&self, a: usize, b: usize) -> Vec<usize> {
+        self.vertex_faces[a]
+            .iter()
+            .filter(|&&face| self.faces[face].contains(&b))
+            .copied()
+            .collect()
+    }
+}
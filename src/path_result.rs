@@ -0,0 +1,180 @@
+use crate::path_point::PathPoint;
+use crate::{Data, PathOptions, PathStats, PathStatus, Shortening};
+use std::f32::consts::PI;
+use wasm_bindgen::prelude::*;
+
+///Path with a certificate of its local optimality: angle sums on both sides of every mesh vertex
+///the path bends at.
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct PathResult {
+    positions: Vec<f32>,
+    lengths: Vec<f64>,
+    cost: f64,
+    joints: Vec<usize>,
+    left_angles: Vec<f32>,
+    right_angles: Vec<f32>,
+    status: PathStatus,
+    stats: PathStats,
+    angle_tolerance: f32,
+}
+
+#[wasm_bindgen]
+impl PathResult {
+    ///Path points, the same as returned by `get_path`.
+    #[wasm_bindgen(getter)]
+    pub fn positions(&self) -> Vec<f32> {
+        self.positions.clone()
+    }
+
+    ///Distance along the path from the start to every point of `positions`.
+    #[wasm_bindgen(getter)]
+    pub fn lengths(&self) -> Vec<f64> {
+        self.lengths.clone()
+    }
+
+    ///Path length, the last of `lengths`. Zero if there is no path.
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> f64 {
+        self.lengths.last().copied().unwrap_or(0.0)
+    }
+
+    ///Sum of segment lengths multiplied by the costs of the faces they cross, as minimized by
+    ///weighted queries. The same as `length` without costs.
+    #[wasm_bindgen(getter)]
+    pub fn cost(&self) -> f64 {
+        self.cost
+    }
+
+    ///Length of the Dijkstra path along mesh edges the path was shortened from.
+    #[wasm_bindgen(getter)]
+    pub fn dijkstra_length(&self) -> f64 {
+        self.stats.dijkstra_length
+    }
+
+    ///Shortening iterations run.
+    #[wasm_bindgen(getter)]
+    pub fn iterations(&self) -> usize {
+        self.stats.iterations
+    }
+
+    ///Whether shortening converged or was stopped by a limit of `PathOptions`.
+    #[wasm_bindgen(getter)]
+    pub fn status(&self) -> PathStatus {
+        self.status
+    }
+
+    ///Work done by the query.
+    #[wasm_bindgen(getter)]
+    pub fn stats(&self) -> PathStats {
+        self.stats
+    }
+
+    ///Indices of interior path points that are mesh vertices. The path is straight across edges,
+    ///so only these can bend it.
+    #[wasm_bindgen(getter)]
+    pub fn joints(&self) -> Vec<usize> {
+        self.joints.clone()
+    }
+
+    ///Sum of face angles on the left of the path at every joint. Infinite if the side is open to
+    ///the mesh boundary.
+    #[wasm_bindgen(getter)]
+    pub fn left_angles(&self) -> Vec<f32> {
+        self.left_angles.clone()
+    }
+
+    ///Sum of face angles on the right of the path at every joint.
+    #[wasm_bindgen(getter)]
+    pub fn right_angles(&self) -> Vec<f32> {
+        self.right_angles.clone()
+    }
+
+    ///Smaller of the angle sums at every joint.
+    #[wasm_bindgen(getter)]
+    pub fn min_angles(&self) -> Vec<f32> {
+        self.left_angles
+            .iter()
+            .zip(self.right_angles.iter())
+            .map(|(left, right)| left.min(*right))
+            .collect()
+    }
+
+    ///Whether no joint is below π by more than the angle tolerance of the query, so shortening
+    ///can't shorten the path any further.
+    #[wasm_bindgen(getter)]
+    pub fn geodesic(&self) -> bool {
+        self.min_angles()
+            .iter()
+            .all(|angle| *angle >= PI - self.angle_tolerance)
+    }
+}
+
+impl PathResult {
+    pub(crate) fn positions_slice(&self) -> &[f32] {
+        &self.positions
+    }
+
+    pub(crate) fn lengths_slice(&self) -> &[f64] {
+        &self.lengths
+    }
+}
+
+///Computes the path between `start` and `end` like `get_path` together with its certificate.
+#[wasm_bindgen]
+pub fn compute_path(
+    start: usize,
+    end: usize,
+    raw_coordinates: Vec<f32>,
+    indices: Vec<usize>,
+) -> PathResult {
+    compute_path_with_options(start, end, &PathOptions::new(), raw_coordinates, indices)
+}
+
+///Computes the path like `compute_path` within the limits of `options`. A truncated path has a
+///status other than `Converged`.
+#[wasm_bindgen]
+pub fn compute_path_with_options(
+    start: usize,
+    end: usize,
+    options: &PathOptions,
+    raw_coordinates: Vec<f32>,
+    indices: Vec<usize>,
+) -> PathResult {
+    let data = Data::new(raw_coordinates, indices);
+    let shortening = data.shorten_path_with_options(start, end, options);
+    data.path_result(&shortening, options)
+}
+
+impl Data {
+    ///Collects the result of a path shortened with `options`. A query that hasn't ended has the
+    ///status `IterationLimit`.
+    pub(crate) fn path_result(&self, shortening: &Shortening, options: &PathOptions) -> PathResult {
+        let (path, query) = (&shortening.path, &shortening.query);
+        let status = shortening.status.unwrap_or(PathStatus::IterationLimit);
+        let mut result = PathResult {
+            positions: self.path_coordinates(path),
+            lengths: self.cumulative_lengths(path),
+            cost: self.path_cost(query, path),
+            joints: vec![],
+            left_angles: vec![],
+            right_angles: vec![],
+            status,
+            stats: self.finish(query),
+            angle_tolerance: options.angle_tolerance(),
+        };
+        for index in 1..path.len().saturating_sub(1) {
+            if let PathPoint::Vertex(_) = path[index] {
+                let [right, left] = self.get_wedges(query, path, index);
+                result.joints.push(index);
+                result
+                    .left_angles
+                    .push(left.map_or(f32::INFINITY, |wedge| wedge.1));
+                result
+                    .right_angles
+                    .push(right.map_or(f32::INFINITY, |wedge| wedge.1));
+            }
+        }
+        result
+    }
+}
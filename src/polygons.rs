@@ -0,0 +1,198 @@
+use crate::{Data, Mesh, PathOptions};
+use na::Vector3;
+use nalgebra as na;
+use wasm_bindgen::prelude::*;
+
+///Triangles of a polygon mesh, with the polygon every triangle came from.
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct Triangulation {
+    indices: Vec<usize>,
+    polygons: Vec<usize>,
+}
+
+#[wasm_bindgen]
+impl Triangulation {
+    ///Index buffer of the triangles, three indices per triangle.
+    #[wasm_bindgen(getter)]
+    pub fn indices(&self) -> Vec<usize> {
+        self.indices.clone()
+    }
+
+    ///Input polygon of every triangle.
+    #[wasm_bindgen(getter)]
+    pub fn polygons(&self) -> Vec<usize> {
+        self.polygons.clone()
+    }
+}
+
+impl Triangulation {
+    pub fn new(raw_coordinates: &[f32], face_sizes: &[usize], indices: &[usize]) -> Triangulation {
+        let mut triangulation = Triangulation {
+            indices: vec![],
+            polygons: vec![],
+        };
+        let mut start: usize = 0;
+        for (polygon, &size) in face_sizes.iter().enumerate() {
+            let end = match start.checked_add(size) {
+                Some(end) => end,
+                None => break,
+            };
+            let vertices = match indices.get(start..end) {
+                Some(vertices) => vertices,
+                None => break,
+            };
+            start = end;
+            let vertex_count = raw_coordinates.len() / 3;
+            for triangle in ear_clipping(raw_coordinates, vertices) {
+                let [a, b, c] = triangle;
+                if triangle.iter().any(|&i| i >= vertex_count) || a == b || b == c || c == a {
+                    continue;
+                }
+                triangulation.indices.extend_from_slice(&triangle);
+                triangulation.polygons.push(polygon);
+            }
+        }
+        triangulation
+    }
+}
+
+///Splits the polygon faces of an OBJ style index buffer into triangles. Face `i` has
+///`face_sizes[i]` vertices following those of the faces before it. Faces of less than three
+///vertices are skipped, and so are faces past the end of `indices`.
+#[wasm_bindgen]
+pub fn triangulate_polygons(
+    raw_coordinates: Vec<f32>,
+    face_sizes: Vec<usize>,
+    indices: Vec<usize>,
+) -> Triangulation {
+    Triangulation::new(&raw_coordinates, &face_sizes, &indices)
+}
+
+#[wasm_bindgen]
+impl Mesh {
+    ///Builds the mesh of polygon faces given like `triangulate_polygons`, e.g. quads. Face
+    ///indices taken and returned by the mesh refer to the polygons. Barycentric coordinates refer
+    ///to triangles instead: `SurfacePoint::triangle` and the points of
+    ///`distance_field_from_points`.
+    pub fn from_polygons(
+        raw_coordinates: Vec<f32>,
+        face_sizes: Vec<usize>,
+        indices: Vec<usize>,
+    ) -> Mesh {
+        let triangulation = Triangulation::new(&raw_coordinates, &face_sizes, &indices);
+        let mut mesh = Mesh::from_data(Data::new(raw_coordinates, triangulation.indices));
+        mesh.polygons = Some(triangulation.polygons);
+        mesh
+    }
+}
+
+impl Mesh {
+    ///Gets the input polygon of face `face`.
+    pub(crate) fn polygon(&self, face: usize) -> usize {
+        self.polygons
+            .as_ref()
+            .and_then(|polygons| polygons.get(face).copied())
+            .unwrap_or(face)
+    }
+
+    ///Gets the faces split from input polygon `polygon`. They follow each other, in the order of
+    ///their polygons.
+    fn triangles(&self, polygon: usize) -> std::ops::Range<usize> {
+        match self.polygons.as_ref() {
+            Some(polygons) => {
+                polygons.partition_point(|&x| x < polygon)
+                    ..polygons.partition_point(|&x| x <= polygon)
+            }
+            None => polygon..polygon + 1,
+        }
+    }
+
+    ///Gets `options` with blocked faces and the patch given as faces instead of polygons, and
+    ///blocked vertices as welded vertices.
+    pub(crate) fn face_options(&self, options: &PathOptions) -> PathOptions {
+        let mut options = options.clone();
+        let vertices = options.blocked_vertices();
+        options.set_blocked_vertices(vertices.into_iter().map(|x| self.vertex(x)).collect());
+        if self.polygons.is_some() {
+            let faces = |polygons: Vec<usize>| -> Vec<usize> {
+                polygons
+                    .into_iter()
+                    .flat_map(|x| self.triangles(x))
+                    .collect()
+            };
+            options.set_blocked_faces(faces(options.blocked_faces()));
+            options.set_patch(options.patch().map(faces));
+        }
+        options
+    }
+
+    ///Gets the cost of every face from the costs of the input polygons.
+    pub(crate) fn face_costs(&self, costs: Vec<f32>) -> Vec<f32> {
+        match self.polygons.as_ref() {
+            Some(polygons) => polygons
+                .iter()
+                .map(|&polygon| costs.get(polygon).copied().unwrap_or(1.0))
+                .collect(),
+            None => costs,
+        }
+    }
+}
+
+///Triangulates the polygon `vertices` by cutting off the ear with the shortest diagonal until
+///a triangle is left, so that quads are split along their shorter diagonal. Ears are found in
+///the plane of the polygon's Newell normal. Polygons without ears, e.g. degenerate ones, are
+///split into a fan.
+fn ear_clipping(raw_coordinates: &[f32], vertices: &[usize]) -> Vec<[usize; 3]> {
+    let position = |vertex: usize| match raw_coordinates.get(3 * vertex..3 * vertex + 3) {
+        Some(x) => Vector3::new(x[0], x[1], x[2]),
+        None => Vector3::zeros(),
+    };
+    let normal = (0..vertices.len()).fold(Vector3::zeros(), |normal, i| {
+        let (a, b) = (
+            position(vertices[i]),
+            position(vertices[(i + 1) % vertices.len()]),
+        );
+        normal + a.cross(&b)
+    });
+    let mut remaining = vertices.to_vec();
+    let mut triangles = vec![];
+    while remaining.len() > 3 {
+        let count = remaining.len();
+        let corner = |i: usize| [count - 1, 0, 1].map(|offset| remaining[(i + offset) % count]);
+        let is_ear = |i: usize| {
+            let [a, b, c] = corner(i).map(position);
+            if (b - a).cross(&(c - b)).dot(&normal) <= 0.0 {
+                return false;
+            }
+            remaining.iter().all(|&vertex| {
+                corner(i).contains(&vertex) || !inside(position(vertex), [a, b, c], &normal)
+            })
+        };
+        let diagonal = |i: usize| {
+            let [a, _, c] = corner(i).map(position);
+            (c - a).magnitude()
+        };
+        let ear = (0..count)
+            .filter(|&i| is_ear(i))
+            .min_by(|&i, &j| diagonal(i).total_cmp(&diagonal(j)));
+        match ear {
+            Some(i) => {
+                triangles.push(corner(i));
+                remaining.remove(i);
+            }
+            None => break,
+        }
+    }
+    for i in 1..remaining.len().saturating_sub(1) {
+        triangles.push([remaining[0], remaining[i], remaining[i + 1]]);
+    }
+    triangles
+}
+
+///Whether `point` lies in the triangle `[a, b, c]`, winding around `normal`, or on its border.
+fn inside(point: Vector3<f32>, [a, b, c]: [Vector3<f32>; 3], normal: &Vector3<f32>) -> bool {
+    [(a, b), (b, c), (c, a)]
+        .iter()
+        .all(|(from, to)| (to - from).cross(&(point - from)).dot(normal) >= 0.0)
+}
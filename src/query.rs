@@ -0,0 +1,102 @@
+use crate::options::now;
+use crate::{Data, Mesh, PathOptions, PathResult, PathStatus, Shortening};
+use js_sys::{Function, Promise};
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::{future_to_promise, JsFuture};
+
+///Time `compute_path_async` runs between yields to the event loop, in milliseconds.
+const SLICE_TIME: f64 = 10.0;
+
+///Path query run in steps, so that the caller decides when to yield.
+#[wasm_bindgen]
+pub struct PathQuery {
+    data: Rc<Data>,
+    shortening: Shortening,
+    options: PathOptions,
+}
+
+#[wasm_bindgen]
+impl PathQuery {
+    ///Runs up to `iterations` shortening iterations. Returns whether shortening has ended.
+    pub fn step(&mut self, iterations: usize) -> bool {
+        for _ in 0..iterations {
+            if self
+                .data
+                .shorten_step(&mut self.shortening, &self.options)
+                .is_some()
+            {
+                break;
+            }
+        }
+        self.shortening.status.is_some()
+    }
+
+    ///Shortening iterations run so far.
+    #[wasm_bindgen(getter)]
+    pub fn iterations(&self) -> usize {
+        self.shortening.iterations
+    }
+
+    ///Length of the path found so far.
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> f64 {
+        let lengths = self.data.cumulative_lengths(&self.shortening.path);
+        lengths.last().copied().unwrap_or(0.0)
+    }
+
+    ///How shortening ended, if it has.
+    #[wasm_bindgen(getter)]
+    pub fn status(&self) -> Option<PathStatus> {
+        self.shortening.status
+    }
+
+    ///Gets the path found so far. A query that hasn't ended has the status `IterationLimit`.
+    pub fn result(&self) -> PathResult {
+        self.data.path_result(&self.shortening, &self.options)
+    }
+}
+
+#[wasm_bindgen]
+impl Mesh {
+    ///Starts a path query between `start` and `end`, running the Dijkstra search. Shortening is
+    ///left to `PathQuery::step`.
+    pub fn query(&self, start: usize, end: usize, options: &PathOptions) -> PathQuery {
+        let (start, end) = (self.vertex(start), self.vertex(end));
+        let options = self.face_options(options);
+        PathQuery {
+            data: self.data.clone(),
+            shortening: self.data.start_shortening(start, end, &options),
+            options,
+        }
+    }
+
+    ///Computes the path like `compute_path`, yielding to the event loop every 10 ms so that the
+    ///page stays responsive. Resolves to a `PathResult`.
+    pub fn compute_path_async(&self, start: usize, end: usize, options: &PathOptions) -> Promise {
+        let mut query = self.query(start, end, options);
+        future_to_promise(async move {
+            loop {
+                let slice = now();
+                while now() - slice < SLICE_TIME {
+                    if query.step(1) {
+                        return Ok(query.result().into());
+                    }
+                }
+                yield_now().await?;
+            }
+        })
+    }
+}
+
+///Waits for a `setTimeout` callback, letting the host render and handle events. Works in
+///windows and workers alike.
+async fn yield_now() -> Result<(), JsValue> {
+    let set_timeout = js_sys::Reflect::get(&js_sys::global(), &JsValue::from("setTimeout"))?;
+    let set_timeout: Function = set_timeout.dyn_into()?;
+    let promise = Promise::new(&mut |resolve, _| {
+        let _ = set_timeout.call1(&JsValue::NULL, &resolve);
+    });
+    JsFuture::from(promise).await.map(|_| ())
+}
@@ -0,0 +1,56 @@
+use na::Vector3;
+use nalgebra as na;
+use wasm_bindgen::prelude::*;
+
+///Point on the mesh surface, given by a face of the input index buffer and barycentric
+///coordinates relative to that face's vertices in input order. On meshes built `from_polygons`,
+///`face` is the input polygon and the coordinates are relative to `triangle`.
+#[wasm_bindgen]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SurfacePoint {
+    pub face: usize,
+    pub(crate) triangle: usize,
+    barycentric: Vector3<f32>,
+    position: Vector3<f32>,
+}
+
+impl SurfacePoint {
+    pub fn new(face: usize, barycentric: Vector3<f32>, position: Vector3<f32>) -> SurfacePoint {
+        SurfacePoint {
+            face,
+            triangle: face,
+            barycentric,
+            position,
+        }
+    }
+
+    pub fn barycentric_vector(&self) -> Vector3<f32> {
+        self.barycentric
+    }
+
+    pub fn position_vector(&self) -> Vector3<f32> {
+        self.position
+    }
+}
+
+#[wasm_bindgen]
+impl SurfacePoint {
+    ///Triangle of the mesh containing the point, which `barycentric` refers to. Differs from
+    ///`face` on meshes built `from_polygons` only.
+    #[wasm_bindgen(getter)]
+    pub fn triangle(&self) -> usize {
+        self.triangle
+    }
+
+    ///Barycentric coordinates `[u, v, w]` of the point within `triangle`.
+    #[wasm_bindgen(getter)]
+    pub fn barycentric(&self) -> Vec<f32> {
+        vec![self.barycentric.x, self.barycentric.y, self.barycentric.z]
+    }
+
+    ///Cartesian coordinates `[x, y, z]` of the point, in input units.
+    #[wasm_bindgen(getter)]
+    pub fn position(&self) -> Vec<f32> {
+        vec![self.position.x, self.position.y, self.position.z]
+    }
+}
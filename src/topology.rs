@@ -0,0 +1,91 @@
+use crate::triangleExtended::TriangleExtended;
+use crate::{v2_from_v3, Data};
+use na::{Matrix4, Vector2, Vector3};
+use nalgebra as na;
+
+impl Data {
+    ///Returns faces around `vertex` in their winding order and whether the fan is closed.
+    pub(crate) fn vertex_fan(&self, vertex: usize) -> (Vec<usize>, bool) {
+        let faces = &self.vertex_faces[vertex];
+        if faces.is_empty() {
+            return (vec![], false);
+        }
+        let rotated: Vec<[usize; 3]> = faces
+            .iter()
+            .map(|&face| self.rotated_face(face, vertex))
+            .collect();
+        let first = (0..faces.len())
+            .find(|&i| !rotated.iter().any(|other| other[2] == rotated[i][1]))
+            .unwrap_or(0);
+
+        let mut fan = vec![faces[first]];
+        let mut current = first;
+        loop {
+            match (0..faces.len()).find(|&i| rotated[i][1] == rotated[current][2]) {
+                Some(next) if next == first => return (fan, true),
+                Some(next) if fan.len() < faces.len() => {
+                    fan.push(faces[next]);
+                    current = next;
+                }
+                _ => return (fan, false),
+            }
+        }
+    }
+
+    ///Returns indices of `face` rotated so that `vertex` comes first, keeping the winding order.
+    pub(crate) fn rotated_face(&self, face: usize, vertex: usize) -> [usize; 3] {
+        let [a, b, c] = self.faces[face];
+        if b == vertex {
+            [b, c, a]
+        } else if c == vertex {
+            [c, a, b]
+        } else {
+            [a, b, c]
+        }
+    }
+
+    ///Gets interior angle of `face` at `vertex`.
+    pub(crate) fn face_angle(&self, face: usize, vertex: usize) -> f32 {
+        match self.get_triangle(self.rotated_face(face, vertex)).angles() {
+            Some(angles) => angles[0],
+            None => 0.0,
+        }
+    }
+
+    ///Unfolds `face` into the plane, given plane positions of its edge `edge`, which must follow
+    ///the face winding. Returns the plane position of the remaining vertex.
+    pub(crate) fn unfold_face(
+        &self,
+        face: usize,
+        edge: [usize; 2],
+        positions: [Vector2<f32>; 2],
+    ) -> Vector2<f32> {
+        let indices = self.rotated_face(face, edge[0]);
+        let mut triangle = TriangleExtended::new(indices, self.get_triangle(indices));
+        let basis_from = triangle.get_basis(edge, triangle.triangle);
+        triangle.transform(basis_from, plane_basis(positions[0], positions[1]));
+        v2_from_v3(triangle.triangle_unfolded.unwrap().c)
+    }
+
+    ///Gets the face on the other side of `edge` of `face`, if the edge is not on the boundary.
+    pub(crate) fn opposite_face(&self, face: usize, edge: [usize; 2]) -> Option<usize> {
+        self.vertex_faces[edge[0]]
+            .iter()
+            .find(|&&other| other != face && self.faces[other].contains(&edge[1]))
+            .copied()
+    }
+}
+
+///Gets basis of a plane chart with origin at `origin` and x axis towards `towards`. Its z axis
+///points down, which keeps the winding of faces unfolded with `TriangleExtended::get_basis`.
+pub(crate) fn plane_basis(origin: Vector2<f32>, towards: Vector2<f32>) -> Matrix4<f32> {
+    let x = (towards - origin).normalize().push(0.0);
+    let z = Vector3::new(0.0, 0.0, -1.0);
+    let y = z.cross(&x);
+    Matrix4::from_columns(&[
+        x.to_homogeneous(),
+        y.to_homogeneous(),
+        z.to_homogeneous(),
+        origin.push(0.0).push(1.0),
+    ])
+}
@@ -0,0 +1,137 @@
+use crate::path_point::PathPoint;
+use crate::{Data, QueryState, SCALE};
+use na::Vector3;
+use nalgebra as na;
+use std::f32::consts::PI;
+use wasm_bindgen::prelude::*;
+
+///Tangent vector transported along a path, with the surface normal at every path point.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default)]
+pub struct ParallelTransport {
+    positions: Vec<Vector3<f32>>,
+    tangents: Vec<Vector3<f32>>,
+    normals: Vec<Vector3<f32>>,
+}
+
+#[wasm_bindgen]
+impl ParallelTransport {
+    ///Path points, the same as returned by `get_path`.
+    #[wasm_bindgen(getter)]
+    pub fn positions(&self) -> Vec<f32> {
+        flatten(&self.positions)
+    }
+
+    ///Transported vector at every path point.
+    #[wasm_bindgen(getter)]
+    pub fn tangents(&self) -> Vec<f32> {
+        flatten(&self.tangents)
+    }
+
+    ///Unit surface normal at every path point.
+    #[wasm_bindgen(getter)]
+    pub fn normals(&self) -> Vec<f32> {
+        flatten(&self.normals)
+    }
+}
+
+///Computes the path between `start` and `end` like `get_path` and transports tangent vector
+///`vector` from its start to every path point. A vector of fewer than 3 coordinates gives an
+///empty result.
+#[wasm_bindgen]
+pub fn transport_along_path(
+    start: usize,
+    end: usize,
+    vector: Vec<f32>,
+    raw_coordinates: Vec<f32>,
+    indices: Vec<usize>,
+) -> ParallelTransport {
+    if vector.len() < 3 {
+        return ParallelTransport::default();
+    }
+    let data = Data::new(raw_coordinates, indices);
+    let path = data.shorten_path(start, end);
+    data.transport(&path, Vector3::new(vector[0], vector[1], vector[2]))
+}
+
+impl Data {
+    ///Transports `vector` along `path`. The vector is kept as an angle to the path direction. It
+    ///stays constant across edges, where the path is straight in the unfolding of the faces, and
+    ///turns by the geodesic curvature of the path at mesh vertices.
+    pub(crate) fn transport(&self, path: &[PathPoint], vector: Vector3<f32>) -> ParallelTransport {
+        let mut result = ParallelTransport::default();
+        if path.is_empty() {
+            return result;
+        }
+        let mut frames: Vec<(Vector3<f32>, Vector3<f32>)> = path
+            .windows(2)
+            .map(|segment| {
+                let direction = self.point_position(&segment[1]) - self.point_position(&segment[0]);
+                (
+                    direction.normalize(),
+                    self.segment_normal(&segment[0], &segment[1]),
+                )
+            })
+            .collect();
+        if frames.is_empty() {
+            frames.push((Vector3::x(), self.segment_normal(&path[0], &path[0])));
+        }
+
+        let (tangent, normal) = frames[0];
+        let projected = vector - normal * vector.dot(&normal);
+        let magnitude = projected.magnitude();
+        let mut angle = projected
+            .dot(&normal.cross(&tangent))
+            .atan2(projected.dot(&tangent));
+
+        for i in 0..path.len() {
+            if i > 0 && i < path.len() - 1 {
+                if let [_, Some((_, left))] = self.get_wedges(&QueryState::default(), path, i) {
+                    angle -= PI - left;
+                }
+            }
+            let (tangent, normal) = frames[i.min(frames.len() - 1)];
+            let binormal = normal.cross(&tangent);
+            result.positions.push(self.point_position(&path[i]) / SCALE);
+            result
+                .tangents
+                .push((tangent * angle.cos() + binormal * angle.sin()) * magnitude);
+            result.normals.push(normal);
+        }
+        result
+    }
+
+    ///Gets surface normal along the path segment between `a` and `b`, averaged over the input
+    ///faces the segment touches.
+    fn segment_normal(&self, a: &PathPoint, b: &PathPoint) -> Vector3<f32> {
+        let faces = match (self.face_between(a, b), a, b) {
+            (Some(face), _, _) => vec![face],
+            (None, PathPoint::Vertex(a), PathPoint::Vertex(b)) if a != b => self.edge_faces(*a, *b),
+            _ => a
+                .vertices()
+                .first()
+                .and_then(|&vertex| self.vertex_faces[vertex].first().copied())
+                .into_iter()
+                .collect(),
+        };
+        let mut normal = Vector3::zeros();
+        for face in faces {
+            if let Some(face_normal) = self.get_triangle(self.faces[face]).normal() {
+                normal += face_normal;
+            }
+        }
+        normal
+            .try_normalize(f32::EPSILON)
+            .unwrap_or_else(Vector3::z)
+    }
+}
+
+fn flatten(points: &[Vector3<f32>]) -> Vec<f32> {
+    let mut result = vec![];
+    for point in points.iter() {
+        result.push(point.x);
+        result.push(point.y);
+        result.push(point.z);
+    }
+    result
+}
@@ -1,9 +1,16 @@
 use na::Vector3;
 use nalgebra as na;
-use std::ops::{Add, Sub};
 
 type Point = Vector3<f32>;
 
+///Part of a triangle a point lies on. Edge `i` goes from vertex `i` to the next one.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Feature {
+    Vertex(usize),
+    Edge(usize),
+    Face,
+}
+
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub struct Triangle {
     pub a: Vector3<f32>,
@@ -15,7 +22,7 @@ impl Triangle {
         Triangle { a, b, c }
     }
 
-    pub fn to_array(&self) -> [Vector3<f32>; 3] {
+    pub fn to_array(self) -> [Vector3<f32>; 3] {
         [self.a, self.b, self.c]
     }
 
@@ -47,17 +54,21 @@ impl Triangle {
             return None;
         }
         let [la, lb, lc] = self.sides();
-        let alpha = ((lb.powi(2) + lc.powi(2) - la.powi(2)) / (2.0 * lb * lc)).acos();
-        let beta = ((la.powi(2) + lc.powi(2) - lb.powi(2)) / (2.0 * la * lc)).acos();
+        // Rounding can push the cosines of needle triangles past ±1.
+        let alpha = ((lb.powi(2) + lc.powi(2) - la.powi(2)) / (2.0 * lb * lc))
+            .clamp(-1.0, 1.0)
+            .acos();
+        let beta = ((la.powi(2) + lc.powi(2) - lb.powi(2)) / (2.0 * la * lc))
+            .clamp(-1.0, 1.0)
+            .acos();
         let gamma = std::f32::consts::PI - alpha - beta;
         Some([alpha, beta, gamma])
     }
 
-    ///Gets area of the triangle.
+    ///Gets area of the triangle. Unlike Heron's formula the cross product stays accurate for
+    ///needle triangles.
     pub fn area(&self) -> f32 {
-        let s = self.semiperimeter();
-        let [la, lb, lc] = self.sides();
-        (s * (s - la) * (s - lb) * (s - lc)).sqrt()
+        (self.b - self.a).cross(&(self.c - self.a)).magnitude() / 2.0
     }
 
     ///Converts barycentric coordinates of given point to cartesian coordinate system.
@@ -68,26 +79,63 @@ impl Triangle {
         Point::new(x, y, z)
     }
 
-    ///Converts cartesian coordinates of given point to barycentric coordinate system.
+    ///Converts cartesian coordinates of given point to barycentric coordinate system. Points off
+    ///the triangle plane are projected onto it.
     pub fn cartesian_to_barycentric(&self, pt: &Point) -> Point {
-        let v0 = Point::new(
-            self.b.x - self.a.x,
-            self.b.y - self.a.y,
-            self.b.z - self.a.z,
-        );
-        let v1 = Point::new(
-            self.c.x - self.a.x,
-            self.c.y - self.a.y,
-            self.c.z - self.a.z,
-        );
-        let v2 = Point::new(pt.x - self.a.x, pt.y - self.a.y, pt.z - self.a.z);
-        let den = 1.0 / (v0.x * v1.y - v1.x * v0.y);
-        let v = (v2.x * v1.y - v1.x * v2.y) * den;
-        let w = (v0.x * v2.y - v2.x * v0.y) * den;
+        let v0 = self.b - self.a;
+        let v1 = self.c - self.a;
+        let v2 = pt - self.a;
+        let normal = v0.cross(&v1);
+        let den = 1.0 / normal.dot(&normal);
+        let v = v2.cross(&v1).dot(&normal) * den;
+        let w = v0.cross(&v2).dot(&normal) * den;
         let u = 1.0 - v - w;
         Point::new(u, v, w)
     }
 
+    ///Gets the point of the triangle closest to `pt` and the feature it lies on.
+    pub fn closest_point(&self, pt: &Point) -> (Point, Feature) {
+        // Voronoi regions of the vertices, edges and face, after Ericson's Real-Time Collision
+        // Detection.
+        let ab = self.b - self.a;
+        let ac = self.c - self.a;
+        let ap = pt - self.a;
+        let (d1, d2) = (ab.dot(&ap), ac.dot(&ap));
+        if d1 <= 0.0 && d2 <= 0.0 {
+            return (self.a, Feature::Vertex(0));
+        }
+        let bp = pt - self.b;
+        let (d3, d4) = (ab.dot(&bp), ac.dot(&bp));
+        if d3 >= 0.0 && d4 <= d3 {
+            return (self.b, Feature::Vertex(1));
+        }
+        let vc = d1 * d4 - d3 * d2;
+        if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+            return (self.a + ab * (d1 / (d1 - d3)), Feature::Edge(0));
+        }
+        let cp = pt - self.c;
+        let (d5, d6) = (ab.dot(&cp), ac.dot(&cp));
+        if d6 >= 0.0 && d5 <= d6 {
+            return (self.c, Feature::Vertex(2));
+        }
+        let vb = d5 * d2 - d1 * d6;
+        if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+            return (self.a + ac * (d2 / (d2 - d6)), Feature::Edge(2));
+        }
+        let va = d3 * d6 - d5 * d4;
+        if va <= 0.0 && d4 - d3 >= 0.0 && d5 - d6 >= 0.0 {
+            let t = (d4 - d3) / ((d4 - d3) + (d5 - d6));
+            return (self.b + (self.c - self.b) * t, Feature::Edge(1));
+        }
+        let den = 1.0 / (va + vb + vc);
+        (self.a + ab * (vb * den) + ac * (vc * den), Feature::Face)
+    }
+
+    ///Gets distance from `pt` to the closest point of the triangle.
+    pub fn distance(&self, pt: &Point) -> f32 {
+        (self.closest_point(pt).0 - pt).magnitude()
+    }
+
     ///Gets centroid of the triangle.
     pub fn centroid(&self) -> Point {
         Point::new(
@@ -106,17 +154,10 @@ impl Triangle {
         Some(self.sides().iter().product::<f32>() / (4.0 * self.area()))
     }
 
-    ///Checks whether a given point lies inside the triangle.
+    ///Checks whether a given point lies inside the triangle, after projecting it onto the
+    ///triangle plane.
     pub fn has_point(&self, pt: Point) -> bool {
-        fn sign(a: &Point, b: &Point, c: &Point) -> f32 {
-            ((a.x - c.x) * (b.y - c.y) - (b.x - c.x) * (a.y - c.y)) as f32
-        }
-        let d1 = sign(&pt, &self.a, &self.b);
-        let d2 = sign(&pt, &self.b, &self.c);
-        let d3 = sign(&pt, &self.c, &self.a);
-        let has_neg = (d1 < 0.0) || (d2 < 0.0) || (d3 < 0.0);
-        let has_pos = (d1 > 0.0) || (d2 > 0.0) || (d3 > 0.0);
-        !(has_neg && has_pos)
+        self.cartesian_to_barycentric(&pt).iter().all(|x| *x >= 0.0)
     }
 
     ///Gets the heights of the triangle.
@@ -202,7 +243,7 @@ impl Triangle {
 
     ///Gets perimeter of the triangle.
     pub fn perimeter(&self) -> f32 {
-        return self.sides().iter().sum();
+        self.sides().iter().sum()
     }
 
     ///Gets distance from ray origin to intersection with triangle. Möller & f32rumbore algorithm.
@@ -215,14 +256,14 @@ impl Triangle {
         let e2 = self.c - self.a;
         let pvec = ray_dir.cross(&e2);
         let det = e1.dot(&pvec);
-        if det.abs() < f32::MIN {
+        if det == 0.0 {
             return None;
         }
 
         let inv_det = 1.0 / det;
         let tvec = *ray_orig - self.a;
         let u = tvec.dot(&pvec) * inv_det;
-        if u < 0.0 || u > 1.0 {
+        if !(0.0..=1.0).contains(&u) {
             return None;
         }
 
@@ -269,3 +310,97 @@ impl Triangle {
         Triangle::from_array(sorted)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn point() -> impl Strategy<Value = Point> {
+        (-10.0f32..10.0, -10.0f32..10.0, -10.0f32..10.0).prop_map(|(x, y, z)| Point::new(x, y, z))
+    }
+
+    ///Triangles including needles and coincident or collinear points.
+    fn triangle() -> impl Strategy<Value = Triangle> {
+        (point(), point(), point(), 0.0f32..1.0, 0usize..3).prop_map(
+            |(a, b, c, t, kind)| match kind {
+                0 => Triangle::new(a, b, c),
+                1 => Triangle::new(a, b, a + (b - a) * t + (c - a) * 1e-4),
+                _ => Triangle::new(a, b, a + (b - a) * t),
+            },
+        )
+    }
+
+    proptest! {
+        #[test]
+        fn area_matches_herons_formula(triangle in triangle()) {
+            // Kahan's stable form of Heron's formula on side lengths, in double precision.
+            let corners = triangle.to_array().map(|x| x.cast::<f64>());
+            let mut sides = [0, 1, 2].map(|i| (corners[(i + 1) % 3] - corners[i]).magnitude());
+            sides.sort_by(|a, b| b.total_cmp(a));
+            let [a, b, c] = sides;
+            let product = (a + (b + c)) * (c - (a - b)) * (c + (a - b)) * (a + (b - c));
+            let heron = product.max(0.0).sqrt() / 4.0;
+            let longest = a as f32;
+            let area = triangle.area();
+            prop_assert!(area >= 0.0);
+            prop_assert!(
+                (area - heron as f32).abs() <= 1e-5 * longest * longest + 1e-6,
+                "{} {}", area, heron
+            );
+        }
+
+        #[test]
+        fn barycentric_coordinates_round_trip(
+            corners in [point(), point(), point()],
+            v in 0.0f32..1.0,
+            w in 0.0f32..1.0,
+            height in -5.0f32..5.0,
+        ) {
+            let triangle = Triangle::from_array(corners);
+            let longest = triangle.sides().iter().fold(0.0f32, |a, b| a.max(*b));
+            prop_assume!(triangle.area() > 1e-2 * longest * longest);
+            let barycentric = Point::new(1.0 - v - w, v, w);
+            let on_plane = triangle.barycentric_to_cartesian(&barycentric);
+            // Lifting the point off the plane must not change its coordinates.
+            let point = on_plane + triangle.normal().unwrap() * height;
+            let result = triangle.cartesian_to_barycentric(&point);
+            prop_assert!((result - barycentric).amax() < 1e-3, "{:?} {:?}", result, barycentric);
+        }
+
+        #[test]
+        fn closest_point_is_closest(
+            corners in [point(), point(), point()],
+            target in point(),
+            samples in prop::collection::vec((0.0f32..1.0, 0.0f32..1.0), 20),
+        ) {
+            let triangle = Triangle::from_array(corners);
+            prop_assume!(triangle.area() > 1e-3);
+            let (closest, feature) = triangle.closest_point(&target);
+            let distance = (closest - target).magnitude();
+            for (v, w) in samples.iter() {
+                let (v, w) = if v + w > 1.0 { (1.0 - v, 1.0 - w) } else { (*v, *w) };
+                let sample = triangle.barycentric_to_cartesian(&Point::new(1.0 - v - w, v, w));
+                prop_assert!(distance <= (sample - target).magnitude() + 1e-4);
+            }
+            let barycentric = triangle.cartesian_to_barycentric(&closest);
+            prop_assert!(barycentric.min() > -1e-3);
+            match feature {
+                Feature::Vertex(i) => prop_assert_eq!(closest, triangle.to_array()[i]),
+                Feature::Edge(i) => prop_assert!(barycentric[(i + 2) % 3].abs() < 1e-3),
+                // Straight above or below the face.
+                Feature::Face => prop_assert!(
+                    (target - closest).cross(&triangle.normal().unwrap()).magnitude() < 1e-3
+                ),
+            }
+        }
+
+        #[test]
+        fn angles_sum_to_pi(triangle in triangle()) {
+            if let Some(angles) = triangle.angles() {
+                prop_assert!(angles.iter().all(|angle| (0.0..=std::f32::consts::PI).contains(angle)));
+                prop_assert!((angles.iter().sum::<f32>() - std::f32::consts::PI).abs() < 1e-4);
+            }
+        }
+    }
+}
@@ -4,6 +4,9 @@ use nalgebra as na;
 use std::cell::RefCell;
 use std::rc::{Rc, Weak};
 
+///Segment between two indexed points.
+pub type Segment = [(usize, Vector3<f32>); 2];
+
 #[derive(Debug, Clone)]
 pub struct TriangleExtended {
     pub indices: [usize; 3],
@@ -14,6 +17,16 @@ pub struct TriangleExtended {
 }
 
 impl TriangleExtended {
+    pub fn new(indices: [usize; 3], triangle: Triangle) -> TriangleExtended {
+        TriangleExtended {
+            indices,
+            triangle,
+            triangle_unfolded: None,
+            sub_triangles: vec![],
+            parent: RefCell::new(Weak::new()),
+        }
+    }
+
     pub fn get_basis(&self, edge: [usize; 2], triangle: Triangle) -> Matrix4<f32> {
         let tr = triangle.to_array();
         let a_index = self.indices.iter().position(|&x| x == edge[0]).unwrap();
@@ -25,12 +38,11 @@ impl TriangleExtended {
         let c = tr[c_index];
 
         let x = (b - a).normalize();
-        let z;
-        if b_index == 1 {
-            z = (c - a).cross(&x).normalize();
+        let z = if b_index == 1 {
+            (c - a).cross(&x).normalize()
         } else {
-            z = x.cross(&(c - a)).normalize();
-        }
+            x.cross(&(c - a)).normalize()
+        };
         let y = z.cross(&x);
         Matrix4::from_columns(&[
             x.to_homogeneous(),
@@ -43,7 +55,7 @@ impl TriangleExtended {
     pub fn transform(&mut self, from: Matrix4<f32>, to: Matrix4<f32>) {
         let m = to * from.try_inverse().unwrap();
 
-        let mut transformed = self.triangle.clone();
+        let mut transformed = self.triangle;
         transformed.a = v3_from_v4(m * transformed.a.push(1.0));
         transformed.b = v3_from_v4(m * transformed.b.push(1.0));
         transformed.c = v3_from_v4(m * transformed.c.push(1.0));
@@ -58,111 +70,204 @@ impl TriangleExtended {
         self.sub_triangles.push(Rc::new(RefCell::new(child)));
     }
 
-    pub fn cut(
-        &self,
-        first: (usize, Vector3<f32>),
-        second: (usize, Vector3<f32>),
-    ) -> Vec<TriangleExtended> {
-        let mut result: Vec<TriangleExtended> = vec![];
-        let a = (self.indices[0], self.triangle.a);
-        let b = (self.indices[1], self.triangle.b);
-        let c = (self.indices[2], self.triangle.c);
+    ///Splits the triangle along `segments`, whose ends are vertices of the triangle or points on
+    ///its sides. Segments must not cross each other. Every end on a side splits that side, so the
+    ///result fits triangles split along the same side. Returns nothing if all ends are vertices.
+    pub fn cut(&self, segments: &[Segment]) -> Vec<TriangleExtended> {
+        let corners = self.triangle.to_array();
+        let mut polygon: Vec<(usize, Vector3<f32>)> = vec![];
+        for (i, corner) in corners.iter().enumerate() {
+            polygon.push((self.indices[i], *corner));
+            let mut on_side: Vec<(usize, Vector3<f32>)> = segments
+                .iter()
+                .flatten()
+                .copied()
+                .filter(|point| !self.indices.contains(&point.0) && self.side(point.1) == i)
+                .collect();
+            on_side.sort_by(|a, b| {
+                let a = (a.1 - corner).magnitude();
+                let b = (b.1 - corner).magnitude();
+                a.total_cmp(&b)
+            });
+            on_side.dedup_by_key(|point| point.0);
+            polygon.append(&mut on_side);
+        }
+        if polygon.len() == 3 {
+            return vec![];
+        }
 
-        let sides = [(b, a, c), (c, a, b), (c, b, a)];
+        // Every segment splits the convex piece that has both its ends as non neighbours.
+        let mut pieces = vec![polygon];
+        for [first, second] in segments.iter() {
+            let split = pieces.iter().enumerate().find_map(|(k, piece)| {
+                let n = piece.len();
+                let i = piece.iter().position(|x| x.0 == first.0)?;
+                let j = piece.iter().position(|x| x.0 == second.0)?;
+                let adjacent = i == j || (i + 1) % n == j || (j + 1) % n == i;
+                (!adjacent).then_some((k, i.min(j), i.max(j)))
+            });
+            if let Some((k, i, j)) = split {
+                let piece = pieces.swap_remove(k);
+                pieces.push(piece[i..=j].to_vec());
+                pieces.push(
+                    piece[j..]
+                        .iter()
+                        .chain(piece[..=i].iter())
+                        .copied()
+                        .collect(),
+                );
+            }
+        }
+        pieces.iter().flat_map(|piece| fan(piece)).collect()
+    }
 
-        let side1 = if !self.triangle.to_array().iter().any(|&x| x == first.1) {
-            sides
-                .iter()
-                .find(|(a, b, _)| (b.1 - a.1).cross(&(first.1 - a.1)).magnitude().abs() < 0.1)
-        } else {
-            None
+    ///Gets index of the side closest to `point`, side `i` going from vertex `i` to the next one.
+    fn side(&self, point: Vector3<f32>) -> usize {
+        let corners = self.triangle.to_array();
+        let distance = |i: usize| {
+            let a = corners[i];
+            let edge = corners[(i + 1) % 3] - a;
+            edge.cross(&(point - a)).magnitude() / edge.magnitude()
         };
+        (0..3)
+            .min_by(|&a, &b| distance(a).total_cmp(&distance(b)))
+            .unwrap()
+    }
+}
+
+///Triangulates convex `polygon` as a fan from the vertex that gives the least degenerate
+///triangles.
+fn fan(polygon: &[(usize, Vector3<f32>)]) -> Vec<TriangleExtended> {
+    let n = polygon.len();
+    let triangles = |apex: usize| -> Vec<[(usize, Vector3<f32>); 3]> {
+        (1..n - 1)
+            .map(|k| {
+                [
+                    polygon[apex],
+                    polygon[(apex + k) % n],
+                    polygon[(apex + k + 1) % n],
+                ]
+            })
+            .collect()
+    };
+    let smallest_area = |apex: usize| {
+        triangles(apex)
+            .iter()
+            .map(|x| (x[1].1 - x[0].1).cross(&(x[2].1 - x[0].1)).magnitude())
+            .fold(f32::INFINITY, f32::min)
+    };
+    let apex = (0..n)
+        .max_by(|&a, &b| smallest_area(a).total_cmp(&smallest_area(b)))
+        .unwrap();
+    triangles(apex)
+        .iter()
+        .map(|[a, b, c]| TriangleExtended::new([a.0, b.0, c.0], Triangle::new(a.1, b.1, c.1)))
+        .collect()
+}
 
-        match side1 {
-            Some((a, b, c)) => {
-                result.push(TriangleExtended {
-                    indices: [a.0, first.0, c.0],
-                    triangle: Triangle::new(a.1, first.1, c.1),
-                    triangle_unfolded: None,
-                    sub_triangles: vec![],
-                    parent: RefCell::new(Weak::new()),
-                });
-                result.push(TriangleExtended {
-                    indices: [first.0, b.0, c.0],
-                    triangle: Triangle::new(first.1, b.1, c.1),
-                    triangle_unfolded: None,
-                    sub_triangles: vec![],
-                    parent: RefCell::new(Weak::new()),
-                });
+fn v3_from_v4(v: Vector4<f32>) -> Vector3<f32> {
+    Vector3::new(v.x, v.y, v.z)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn point() -> impl Strategy<Value = Vector3<f32>> {
+        (-10.0f32..10.0, -10.0f32..10.0, -10.0f32..10.0).prop_map(|(x, y, z)| Vector3::new(x, y, z))
+    }
+
+    ///Point on side `side` of `triangle`, with index `10 + side`.
+    fn on_side(triangle: &Triangle, side: usize, t: f32) -> (usize, Vector3<f32>) {
+        let corners = triangle.to_array();
+        let (a, b) = (corners[side], corners[(side + 1) % 3]);
+        (10 + side, a + (b - a) * t)
+    }
+
+    proptest! {
+        #[test]
+        fn cut_pieces_cover_the_triangle(
+            corners in [point(), point(), point()],
+            side in 0usize..3,
+            other in 0usize..2,
+            to_vertex in any::<bool>(),
+            t in 0.05f32..0.95,
+            u in 0.05f32..0.95,
+        ) {
+            let triangle = Triangle::from_array(corners);
+            let longest = triangle.sides().iter().fold(0.0f32, |a, b| a.max(*b));
+            prop_assume!(triangle.area() > 1e-3 * longest * longest);
+            let extended = TriangleExtended::new([0, 1, 2], triangle);
+            let first = on_side(&triangle, side, t);
+            let second = if to_vertex {
+                // The vertex opposite to the side.
+                ((side + 2) % 3, corners[(side + 2) % 3])
+            } else {
+                on_side(&triangle, (side + 1 + other) % 3, u)
+            };
+
+            let pieces = extended.cut(&[[first, second]]);
+            prop_assert!(!pieces.is_empty());
+            let area: f32 = pieces.iter().map(|piece| piece.triangle.area()).sum();
+            prop_assert!((area - triangle.area()).abs() <= 1e-4 * triangle.area());
+            let normal = triangle.normal().unwrap();
+            for piece in pieces.iter() {
+                let [a, b, c] = piece.triangle.to_array();
+                prop_assert!((b - a).cross(&(c - a)).dot(&normal) >= 0.0);
             }
-            None => {}
+            prop_assert!(pieces
+                .iter()
+                .any(|piece| piece.indices.contains(&first.0) && piece.indices.contains(&second.0)));
         }
 
-        let side2 = if !self.triangle.to_array().iter().any(|&x| x == second.1) {
-            sides
-                .iter()
-                .find(|(a, b, _)| (b.1 - a.1).cross(&(second.1 - a.1)).magnitude().abs() < 0.1)
-        } else {
-            None
-        };
+        #[test]
+        fn cuts_along_two_segments_leave_no_t_junctions(
+            corners in [point(), point(), point()],
+            t in 0.05f32..0.45,
+            s in 0.55f32..0.95,
+            u in 0.55f32..0.95,
+            v in 0.05f32..0.95,
+            to_vertex in any::<bool>(),
+        ) {
+            let triangle = Triangle::from_array(corners);
+            let longest = triangle.sides().iter().fold(0.0f32, |a, b| a.max(*b));
+            prop_assume!(triangle.area() > 1e-2 * longest * longest);
+            let extended = TriangleExtended::new([0, 1, 2], triangle);
+            // The first segment cuts off vertex 0, the second one runs beside it.
+            let first = [on_side(&triangle, 0, t), (20, on_side(&triangle, 2, s).1)];
+            let second = [
+                (21, on_side(&triangle, 0, u).1),
+                if to_vertex { (2, corners[2]) } else { on_side(&triangle, 1, v) },
+            ];
 
-        match side2 {
-            Some((l, m, n)) => match side1 {
-                Some(_) => {
-                    let tr: Vec<TriangleExtended> = vec![
-                        TriangleExtended {
-                            indices: [second.0, first.0, l.0],
-                            triangle: Triangle::new(second.1, first.1, l.1),
-                            triangle_unfolded: None,
-                            sub_triangles: vec![],
-                            parent: RefCell::new(Weak::new()),
-                        },
-                        TriangleExtended {
-                            indices: [second.0, m.0, first.0],
-                            triangle: Triangle::new(second.1, m.1, first.1),
-                            triangle_unfolded: None,
-                            sub_triangles: vec![],
-                            parent: RefCell::new(Weak::new()),
-                        },
-                    ];
-                    let s2 = [second.0, first.0, l.0, m.0];
-
-                    let index = result
-                        .iter()
-                        .enumerate()
-                        .find(|(_, x)| x.indices.iter().all(|index| s2.contains(index)))
-                        .unwrap()
-                        .0;
-
-                    if index == 0 {
-                        result.splice(0..1, tr);
-                    } else {
-                        result.splice(1.., tr);
+            let pieces = extended.cut(&[first, second]);
+            let area: f32 = pieces.iter().map(|piece| piece.triangle.area()).sum();
+            prop_assert!((area - triangle.area()).abs() <= 1e-3 * triangle.area());
+            for [a, b] in [first, second] {
+                prop_assert!(pieces
+                    .iter()
+                    .any(|piece| piece.indices.contains(&a.0) && piece.indices.contains(&b.0)));
+            }
+            let points: Vec<(usize, Vector3<f32>)> = (0..3)
+                .map(|i| (i, corners[i]))
+                .chain(first.iter().chain(second.iter()).copied())
+                .collect();
+            for piece in pieces.iter() {
+                let [a, b, c] = piece.triangle.to_array();
+                for (i, [start, end]) in [[a, b], [b, c], [c, a]].iter().enumerate() {
+                    let ends = [piece.indices[i], piece.indices[(i + 1) % 3]];
+                    for (index, point) in points.iter().filter(|x| !ends.contains(&x.0)) {
+                        let edge = end - start;
+                        let along = (point - start).dot(&edge) / edge.norm_squared();
+                        let off = edge.cross(&(point - start)).norm() / edge.norm();
+                        prop_assert!(
+                            along <= 1e-3 || along >= 1.0 - 1e-3 || off > 1e-3 * longest,
+                            "point {} on edge {:?}", index, ends
+                        );
                     }
                 }
-                None => {
-                    result.push(TriangleExtended {
-                        indices: [l.0, second.0, n.0],
-                        triangle: Triangle::new(l.1, first.1, n.1),
-                        triangle_unfolded: None,
-                        sub_triangles: vec![],
-                        parent: RefCell::new(Weak::new()),
-                    });
-                    result.push(TriangleExtended {
-                        indices: [second.0, m.0, n.0],
-                        triangle: Triangle::new(second.1, m.1, n.1),
-                        triangle_unfolded: None,
-                        sub_triangles: vec![],
-                        parent: RefCell::new(Weak::new()),
-                    });
-                }
-            },
-            None => {}
+            }
         }
-        result
     }
 }
-
-fn v3_from_v4(v: Vector4<f32>) -> Vector3<f32> {
-    Vector3::new(v.x, v.y, v.z)
-}
@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+use wasm_bindgen::prelude::*;
+
+///Index buffer with coincident vertices merged, so that faces on both sides of a UV or normal
+///seam share their vertices.
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct Welding {
+    indices: Vec<usize>,
+    vertices: Vec<usize>,
+}
+
+#[wasm_bindgen]
+impl Welding {
+    ///Faces of the input index buffer in the same order, referencing only the vertices kept.
+    ///Use it in place of the input indices with the input coordinates.
+    #[wasm_bindgen(getter)]
+    pub fn indices(&self) -> Vec<usize> {
+        self.indices.clone()
+    }
+
+    ///Vertex kept for every input vertex: the first one in input order within the tolerance.
+    ///Kept vertices map to themselves.
+    #[wasm_bindgen(getter)]
+    pub fn vertices(&self) -> Vec<usize> {
+        self.vertices.clone()
+    }
+}
+
+impl Welding {
+    pub fn new(raw_coordinates: &[f32], indices: &[usize], tolerance: f32) -> Welding {
+        let vertices = kept_vertices(raw_coordinates, tolerance);
+        Welding {
+            indices: indices
+                .iter()
+                .map(|&index| vertices.get(index).copied().unwrap_or(index))
+                .collect(),
+            vertices,
+        }
+    }
+
+    ///Gets the vertex kept for input vertex `vertex`. Out of range vertices are left as they are.
+    pub fn vertex(&self, vertex: usize) -> usize {
+        self.vertices.get(vertex).copied().unwrap_or(vertex)
+    }
+}
+
+///Merges vertices closer than `tolerance` in input units and returns the merged index buffer with
+///the vertex mapping. Every kept vertex is an input vertex, so indices in later results refer
+///to the input coordinates.
+#[wasm_bindgen]
+pub fn weld_vertices(raw_coordinates: Vec<f32>, indices: Vec<usize>, tolerance: f32) -> Welding {
+    Welding::new(&raw_coordinates, &indices, tolerance)
+}
+
+///Maps every vertex to the first kept vertex within `tolerance`, found through a hash grid
+///with cells of the tolerance size, so only the 27 cells around a vertex are searched.
+fn kept_vertices(raw_coordinates: &[f32], tolerance: f32) -> Vec<usize> {
+    let tolerance = tolerance.max(0.0);
+    let cell_size = if tolerance > 0.0 { tolerance } else { 1.0 };
+    let mut grid: HashMap<[i64; 3], Vec<usize>> = HashMap::new();
+    let mut vertices = Vec::with_capacity(raw_coordinates.len() / 3);
+    for (index, point) in raw_coordinates.chunks_exact(3).enumerate() {
+        let cell = [0, 1, 2].map(|k| (point[k] / cell_size).floor() as i64);
+        let mut kept = index;
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                for dz in -1..=1 {
+                    let neighbour = [0, 1, 2].map(|k| cell[k].saturating_add([dx, dy, dz][k]));
+                    for &other in grid.get(&neighbour).into_iter().flatten() {
+                        let other_point = &raw_coordinates[3 * other..3 * other + 3];
+                        let distance = (0..3)
+                            .map(|k| (point[k] - other_point[k]).powi(2))
+                            .sum::<f32>()
+                            .sqrt();
+                        if distance <= tolerance {
+                            kept = kept.min(other);
+                        }
+                    }
+                }
+            }
+        }
+        if kept == index {
+            grid.entry(cell).or_default().push(index);
+        }
+        vertices.push(kept);
+    }
+    vertices
+}
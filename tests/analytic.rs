@@ -0,0 +1,174 @@
+//! Path lengths and shapes on tessellated surfaces with known geodesics.
+//!
+//! Plane, prism and pyramid meshes are developable, so their shortest paths are exact straight
+//! lines once unfolded and lengths must match to float precision (`EXACT`). Sphere paths are
+//! compared to the great circle of the smooth sphere, which the inscribed mesh undercuts by an
+//! amount falling with resolution.
+
+mod common;
+
+use common::*;
+use geodesic_path::PathStatus;
+use std::f32::consts::PI;
+
+///Relative length error allowed on developable meshes.
+const EXACT: f32 = 1e-4;
+
+fn assert_relative(actual: f32, expected: f32, tolerance: f32) {
+    assert!(
+        ((actual - expected) / expected).abs() <= tolerance,
+        "length {} expected {} within {}",
+        actual,
+        expected,
+        tolerance
+    );
+}
+
+///Checks that a path converged, certifies itself, runs between the given vertices and reports
+///its own length.
+fn assert_path(mesh: &Mesh, start: usize, end: usize) -> Vec<[f32; 3]> {
+    let result = mesh.path(start, end);
+    assert_eq!(result.status(), PathStatus::Converged);
+    assert!(result.geodesic(), "min angles {:?}", result.min_angles());
+    let points = points(&result.positions());
+    assert!(distance(points[0], mesh.vertex(start)) < 1e-5);
+    assert!(distance(points[points.len() - 1], mesh.vertex(end)) < 1e-5);
+
+    let lengths = result.lengths();
+    assert_eq!(lengths.len(), points.len());
+    assert_eq!(lengths[0], 0.0);
+    for (i, pair) in points.windows(2).enumerate() {
+        let segment = (lengths[i + 1] - lengths[i]) as f32;
+        assert!((segment - distance(pair[0], pair[1])).abs() < 1e-5);
+    }
+    assert_eq!(result.length(), lengths[lengths.len() - 1]);
+    assert!(result.length() <= result.dijkstra_length() * (1.0 + 1e-6));
+    points
+}
+
+#[test]
+fn plane_paths_are_straight() {
+    for &n in [4, 10, 25].iter() {
+        let mesh = plane(n, 1.0);
+        let corners = [0, n, n * (n + 1), (n + 1) * (n + 1) - 1];
+        let targets = [n * (n + 1) + n / 3, (n + 1) * (n / 2) + n, n];
+        for &start in corners.iter() {
+            for &end in targets.iter().filter(|&&end| end != start) {
+                let points = assert_path(&mesh, start, end);
+                let (a, b) = (mesh.vertex(start), mesh.vertex(end));
+                assert_relative(path_length(&points), distance(a, b), EXACT);
+                for point in points.iter() {
+                    // Distance from the segment between the ends.
+                    let off = distance(a, *point) + distance(*point, b) - distance(a, b);
+                    assert!(off < 1e-4, "point {:?} off the segment by {}", point, off);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn cylinder_paths_unroll_to_straight_lines() {
+    let (radius, height) = (1.0f32, 3.0);
+    for &(around, along) in [(8, 6), (16, 12), (32, 24)].iter() {
+        let mesh = cylinder(around, along, radius, height);
+        let side = 2.0 * radius * (PI / around as f32).sin();
+        for &(column, row) in [(around / 2, along), (around / 4, along / 2), (1, along)].iter() {
+            let end = row * around + column;
+            let points = assert_path(&mesh, 0, end);
+            let columns = column.min(around - column) as f32;
+            let expected = (columns * side).hypot(height * row as f32 / along as f32);
+            assert_relative(path_length(&points), expected, EXACT);
+        }
+    }
+}
+
+#[test]
+fn cone_paths_unroll_to_straight_lines() {
+    let (radius, height) = (1.0f32, 2.0);
+    let slant = radius.hypot(height);
+    for &(around, rings) in [(8, 4), (16, 8), (32, 16)].iter() {
+        let mesh = cone(around, rings, radius, height);
+        // Apex angle of every side, so the cone unrolls into a sector below π.
+        let apex = 2.0 * (radius * (PI / around as f32).sin() / slant).asin();
+        let vertex = |ring: usize, column: usize| 1 + (ring - 1) * around + column;
+        let pairs = [
+            ((rings, 0), (rings, around / 2)),
+            ((rings / 2, 0), (rings, around / 3)),
+            ((1, 1), (rings, around - 2)),
+        ];
+        for &((ring_a, column_a), (ring_b, column_b)) in pairs.iter() {
+            let points = assert_path(&mesh, vertex(ring_a, column_a), vertex(ring_b, column_b));
+            let columns = column_b - column_a;
+            let angle = columns.min(around - columns) as f32 * apex;
+            let (a, b) = (
+                slant * ring_a as f32 / rings as f32,
+                slant * ring_b as f32 / rings as f32,
+            );
+            let expected = (a * a + b * b - 2.0 * a * b * angle.cos()).sqrt();
+            assert_relative(path_length(&points), expected, EXACT);
+        }
+    }
+}
+
+#[test]
+fn sphere_paths_approach_great_circles() {
+    // Relative length tolerance and distance from the great circle plane per resolution, about
+    // twice the observed errors. Lengths converge quadratically, shapes linearly.
+    let resolutions = [
+        (16, 8, 0.03, 0.08),
+        (32, 16, 0.008, 0.04),
+        (64, 32, 0.002, 0.02),
+    ];
+    for &(around, along, tolerance, max_off) in resolutions.iter() {
+        let mesh = sphere(around, along);
+        let vertex = |row: usize, column: usize| 1 + (row - 1) * around + column;
+        let pairs = [
+            (0, vertex(along / 2, 0)),
+            (vertex(along / 4, 0), vertex(along / 2, around / 4)),
+            (vertex(along / 2, 0), vertex(3 * along / 4, around / 3)),
+        ];
+        for &(start, end) in pairs.iter() {
+            let points = assert_path(&mesh, start, end);
+            let (a, b) = (mesh.vertex(start), mesh.vertex(end));
+            let dot: f32 = (0..3).map(|k| a[k] * b[k]).sum();
+            let expected = dot.clamp(-1.0, 1.0).acos();
+            assert_relative(path_length(&points), expected, tolerance);
+            // The path stays close to the plane of the great circle.
+            let normal = [
+                a[1] * b[2] - a[2] * b[1],
+                a[2] * b[0] - a[0] * b[2],
+                a[0] * b[1] - a[1] * b[0],
+            ];
+            let norm = distance(normal, [0.0; 3]);
+            for point in points.iter() {
+                let off: f32 = (0..3).map(|k| point[k] * normal[k]).sum::<f32>() / norm;
+                assert!(off.abs() < max_off, "point {:?} off by {}", point, off);
+            }
+        }
+    }
+}
+
+#[test]
+fn cube_paths_unfold_across_sides() {
+    for &n in [2, 4, 8].iter() {
+        let mesh = cube(n);
+        let find = |point: [f32; 3]| {
+            (0..mesh.coordinates.len() / 3)
+                .find(|&i| distance(mesh.vertex(i), point) < 1e-5)
+                .unwrap()
+        };
+        let cases = [
+            // Opposite corners, across two sides unfolded into a 2 by 4 rectangle.
+            ([-1.0, -1.0, -1.0], [1.0, 1.0, 1.0], 20f32.sqrt()),
+            // Centres of adjacent sides.
+            ([0.0, 0.0, 1.0], [1.0, 0.0, 0.0], 2.0),
+            // Corner to the centre of the opposite side.
+            ([-1.0, -1.0, -1.0], [0.0, 0.0, 1.0], 10f32.sqrt()),
+        ];
+        for &(a, b, expected) in cases.iter() {
+            let points = assert_path(&mesh, find(a), find(b));
+            assert_relative(path_length(&points), expected, EXACT);
+        }
+    }
+}
@@ -0,0 +1,129 @@
+//! Paths around faces and vertices blocked by the query options.
+
+mod common;
+
+use common::*;
+use geodesic_path::{
+    distance_matrix, DistanceMethod, Mesh as PersistentMesh, PathOptions, PathStatus,
+};
+
+///Faces of `plane(10, 1.0)` in the quads from (0.4, 0.3) to (0.6, 0.7).
+fn obstacle() -> Vec<usize> {
+    (3..7)
+        .flat_map(|y| (4..6).flat_map(move |x| [2 * (y * 10 + x), 2 * (y * 10 + x) + 1]))
+        .collect()
+}
+
+fn options(faces: Vec<usize>, vertices: Vec<usize>) -> PathOptions {
+    let mut options = tight_options();
+    options.set_blocked_faces(faces);
+    options.set_blocked_vertices(vertices);
+    options
+}
+
+#[test]
+fn paths_wrap_around_blocked_faces() {
+    let mesh = plane(10, 1.0);
+    let result = mesh.path_with_options(5, 115, &options(obstacle(), vec![]));
+    assert_eq!(result.status(), PathStatus::Converged);
+    for point in points(&result.positions()) {
+        let inside = point[0] > 0.4 + 1e-4
+            && point[0] < 0.6 - 1e-4
+            && point[1] > 0.3 + 1e-4
+            && point[1] < 0.7 - 1e-4;
+        assert!(!inside, "{:?} lies in the obstacle", point);
+    }
+    // Straight to a corner, along the side and straight to the end.
+    let expected = 2.0 * (0.1f64 * 0.1 + 0.3 * 0.3).sqrt() + 0.4;
+    assert!((result.length() - expected).abs() < 1e-4);
+    assert!(result.dijkstra_length() >= result.length());
+
+    let free = mesh.path(5, 115);
+    assert!((free.length() - 1.0).abs() < 1e-5);
+}
+
+#[test]
+fn blocked_vertices_block_their_faces() {
+    let mesh = plane(10, 1.0);
+    let vertices: Vec<usize> = (4..7).map(|y| y * 11 + 5).collect();
+    let faces = mesh
+        .indices
+        .chunks(3)
+        .enumerate()
+        .filter(|(_, face)| face.iter().any(|vertex| vertices.contains(vertex)))
+        .map(|(face, _)| face)
+        .collect();
+    let result = mesh.path_with_options(5, 115, &options(vec![], vertices.clone()));
+    let expected = mesh.path_with_options(5, 115, &options(faces, vec![]));
+    assert_eq!(result.status(), PathStatus::Converged);
+    assert_eq!(result.positions(), expected.positions());
+    assert!(result.length() > 1.0 + 1e-3);
+    for point in points(&result.positions()) {
+        assert!(vertices
+            .iter()
+            .all(|&vertex| distance(point, mesh.vertex(vertex)) > 1e-3));
+    }
+
+    let result = mesh.path_with_options(5, 115, &options(vec![], vec![5]));
+    assert_eq!(result.status(), PathStatus::NoPath);
+}
+
+#[test]
+fn walls_disconnect_the_mesh() {
+    let mesh = plane(10, 1.0);
+    // Every face of the row of quads from y = 0.5 to 0.6.
+    let wall: Vec<usize> = (100..120).collect();
+    let result = mesh.path_with_options(5, 115, &options(wall.clone(), vec![]));
+    assert_eq!(result.status(), PathStatus::NoPath);
+
+    let matrix = distance_matrix(
+        vec![5, 115, 0],
+        DistanceMethod::Edges,
+        &options(wall, vec![]),
+        mesh.coordinates.clone(),
+        mesh.indices.clone(),
+    );
+    assert!(matrix[1].is_infinite());
+    assert!(matrix[2].is_finite());
+}
+
+#[test]
+fn interleaved_queries_keep_their_own_blocked_faces() {
+    let mesh = plane(10, 1.0);
+    let persistent = PersistentMesh::new(mesh.coordinates.clone(), mesh.indices.clone());
+    let (blocked, free) = (options(obstacle(), vec![]), options(vec![], vec![]));
+    let expected = persistent.compute_path(5, 115, &blocked);
+
+    let mut around = persistent.query(5, 115, &blocked);
+    // Started after the blocked query, without blocked faces.
+    let mut straight = persistent.query(5, 115, &free);
+    let (mut around_done, mut straight_done) = (false, false);
+    while !(around_done && straight_done) {
+        around_done = around.step(1);
+        straight_done = straight.step(1);
+    }
+    assert_eq!(around.result().positions(), expected.positions());
+    assert!((straight.result().length() - 1.0).abs() < 1e-5);
+}
+
+#[test]
+fn blocked_vertices_of_welded_meshes_block_their_twins() {
+    let mesh = plane(10, 1.0);
+    // Every face corner has its own vertex, welded back together.
+    let coordinates = mesh.indices.iter().flat_map(|&x| mesh.vertex(x)).collect();
+    let indices = (0..mesh.indices.len()).collect();
+    let welded = PersistentMesh::with_welding(coordinates, indices, 1e-4);
+    // Last copy of a vertex, which welding merges into the first one.
+    let copy = |vertex: usize| mesh.indices.iter().rposition(|&x| x == vertex).unwrap();
+
+    let vertices: Vec<usize> = (4..7).map(|y| y * 11 + 5).collect();
+    let blocked = options(vec![], vertices.iter().map(|&x| copy(x)).collect());
+    let result = welded.compute_path(copy(5), copy(115), &blocked);
+    let expected = mesh.path_with_options(5, 115, &options(vec![], vertices));
+    assert_eq!(result.status(), PathStatus::Converged);
+    assert!(result.length() > 1.0 + 1e-3);
+    assert!((result.length() - expected.length()).abs() < 1e-5);
+
+    let result = welded.compute_path(copy(5), copy(115), &options(vec![], vec![copy(5)]));
+    assert_eq!(result.status(), PathStatus::NoPath);
+}
@@ -0,0 +1,285 @@
+//! Meshes and helpers shared by the native test suites.
+
+#![allow(dead_code)]
+
+use geodesic_path::{compute_path_with_options, PathOptions, PathResult};
+use std::f32::consts::PI;
+
+#[derive(Debug, Clone)]
+pub struct Mesh {
+    pub coordinates: Vec<f32>,
+    pub indices: Vec<usize>,
+}
+
+impl Mesh {
+    pub fn vertex(&self, index: usize) -> [f32; 3] {
+        [
+            self.coordinates[3 * index],
+            self.coordinates[3 * index + 1],
+            self.coordinates[3 * index + 2],
+        ]
+    }
+
+    ///Computes the path between `start` and `end` with `tight_options`.
+    pub fn path(&self, start: usize, end: usize) -> PathResult {
+        self.path_with_options(start, end, &tight_options())
+    }
+
+    pub fn path_with_options(&self, start: usize, end: usize, options: &PathOptions) -> PathResult {
+        compute_path_with_options(
+            start,
+            end,
+            options,
+            self.coordinates.clone(),
+            self.indices.clone(),
+        )
+    }
+}
+
+///Options with a tight angle tolerance and a time limit no test reaches.
+pub fn tight_options() -> PathOptions {
+    let mut options = PathOptions::new();
+    options.set_angle_tolerance(0.001);
+    options.set_time_limit(Some(10000.0));
+    options
+}
+
+///Square of `size` in the z = 0 plane, split into `n` by `n` quads of two triangles.
+pub fn plane(n: usize, size: f32) -> Mesh {
+    let mut coordinates = vec![];
+    for y in 0..=n {
+        for x in 0..=n {
+            coordinates.extend_from_slice(&[
+                x as f32 * size / n as f32,
+                y as f32 * size / n as f32,
+                0.0,
+            ]);
+        }
+    }
+    Mesh {
+        coordinates,
+        indices: quads(n, n, n + 1),
+    }
+}
+
+///Open prism of `around` sides inscribed in a cylinder along z, with `along` rows of quads.
+///Vertex `row * around + column` lies at height `row * height / along`.
+pub fn cylinder(around: usize, along: usize, radius: f32, height: f32) -> Mesh {
+    let mut coordinates = vec![];
+    for row in 0..=along {
+        for column in 0..around {
+            let angle = 2.0 * PI * column as f32 / around as f32;
+            coordinates.extend_from_slice(&[
+                radius * angle.cos(),
+                radius * angle.sin(),
+                height * row as f32 / along as f32,
+            ]);
+        }
+    }
+    Mesh {
+        coordinates,
+        indices: quads(around, along, around),
+    }
+}
+
+///Open pyramid of `around` sides inscribed in a cone with the apex at vertex 0 and the base
+///circle of `radius` at `height` below it. Vertex `1 + (ring - 1) * around + column` lies at
+///`ring / rings` of the way from the apex to the base.
+pub fn cone(around: usize, rings: usize, radius: f32, height: f32) -> Mesh {
+    let mut coordinates = vec![0.0, 0.0, height];
+    for ring in 1..=rings {
+        let scale = ring as f32 / rings as f32;
+        for column in 0..around {
+            let angle = 2.0 * PI * column as f32 / around as f32;
+            coordinates.extend_from_slice(&[
+                radius * scale * angle.cos(),
+                radius * scale * angle.sin(),
+                height * (1.0 - scale),
+            ]);
+        }
+    }
+    let mut indices = vec![];
+    for column in 0..around {
+        indices.extend_from_slice(&[0, 1 + (column + 1) % around, 1 + column]);
+    }
+    indices.extend(quads(around, rings - 1, around).iter().map(|i| i + 1));
+    Mesh {
+        coordinates,
+        indices,
+    }
+}
+
+///Unit UV sphere with `around` meridians and `along` parallels, poles at vertex 0 and the last
+///vertex.
+pub fn sphere(around: usize, along: usize) -> Mesh {
+    let mut coordinates = vec![0.0, 0.0, 1.0];
+    for row in 1..along {
+        let theta = PI * row as f32 / along as f32;
+        for column in 0..around {
+            let phi = 2.0 * PI * column as f32 / around as f32;
+            coordinates.extend_from_slice(&[
+                theta.sin() * phi.cos(),
+                theta.sin() * phi.sin(),
+                theta.cos(),
+            ]);
+        }
+    }
+    coordinates.extend_from_slice(&[0.0, 0.0, -1.0]);
+    let south = coordinates.len() / 3 - 1;
+    let vertex = |row: usize, column: usize| 1 + (row - 1) * around + column % around;
+    let mut indices = vec![];
+    for column in 0..around {
+        indices.extend_from_slice(&[0, vertex(1, column + 1), vertex(1, column)]);
+    }
+    indices.extend(quads(around, along - 2, around).iter().map(|i| i + 1));
+    for column in 0..around {
+        indices.extend_from_slice(&[
+            south,
+            vertex(along - 1, column),
+            vertex(along - 1, column + 1),
+        ]);
+    }
+    Mesh {
+        coordinates,
+        indices,
+    }
+}
+
+///Cube [-1, 1]³ with every side split into `n` by `n` quads of two triangles. Sides share their
+///edge vertices.
+pub fn cube(n: usize) -> Mesh {
+    let sides: [([f32; 3], [f32; 3], [f32; 3]); 6] = [
+        ([-1., -1., 1.], [2., 0., 0.], [0., 2., 0.]),
+        ([1., -1., -1.], [-2., 0., 0.], [0., 2., 0.]),
+        ([1., -1., 1.], [0., 0., -2.], [0., 2., 0.]),
+        ([-1., -1., -1.], [0., 0., 2.], [0., 2., 0.]),
+        ([-1., 1., 1.], [2., 0., 0.], [0., 0., -2.]),
+        ([-1., -1., -1.], [2., 0., 0.], [0., 0., 2.]),
+    ];
+    let mut points: Vec<[f32; 3]> = vec![];
+    let mut indices = vec![];
+    for (origin, u, v) in sides.iter() {
+        let mut ids = vec![vec![0; n + 1]; n + 1];
+        for (y, row) in ids.iter_mut().enumerate() {
+            for (x, id) in row.iter_mut().enumerate() {
+                let (s, t) = (x as f32 / n as f32, y as f32 / n as f32);
+                let point = [0, 1, 2].map(|k| origin[k] + u[k] * s + v[k] * t);
+                *id = find_or_push(&mut points, point);
+            }
+        }
+        for y in 0..n {
+            for x in 0..n {
+                let (a, b) = (ids[y][x], ids[y][x + 1]);
+                let (d, e) = (ids[y + 1][x], ids[y + 1][x + 1]);
+                indices.extend_from_slice(&[a, b, e, a, e, d]);
+            }
+        }
+    }
+    Mesh {
+        coordinates: points.into_iter().flatten().collect(),
+        indices,
+    }
+}
+
+fn find_or_push(points: &mut Vec<[f32; 3]>, point: [f32; 3]) -> usize {
+    match points
+        .iter()
+        .position(|p| (0..3).all(|k| (p[k] - point[k]).abs() < 1e-5))
+    {
+        Some(index) => index,
+        None => {
+            points.push(point);
+            points.len() - 1
+        }
+    }
+}
+
+///Triangles of a grid of `columns` by `rows` quads with `stride` vertices per row. Columns wrap
+///around if `stride` equals `columns`.
+fn quads(columns: usize, rows: usize, stride: usize) -> Vec<usize> {
+    let mut indices = vec![];
+    for y in 0..rows {
+        for x in 0..columns {
+            let a = y * stride + x;
+            let b = y * stride + (x + 1) % stride;
+            let (d, e) = (a + stride, b + stride);
+            indices.extend_from_slice(&[a, b, e, a, e, d]);
+        }
+    }
+    indices
+}
+
+pub fn points(positions: &[f32]) -> Vec<[f32; 3]> {
+    positions.chunks(3).map(|p| [p[0], p[1], p[2]]).collect()
+}
+
+pub fn distance(a: [f32; 3], b: [f32; 3]) -> f32 {
+    (0..3).map(|k| (a[k] - b[k]).powi(2)).sum::<f32>().sqrt()
+}
+
+pub fn path_length(points: &[[f32; 3]]) -> f32 {
+    points
+        .windows(2)
+        .map(|pair| distance(pair[0], pair[1]))
+        .sum()
+}
+
+///Distance from `point` to the closest face of `mesh`.
+pub fn surface_distance(mesh: &Mesh, point: [f32; 3]) -> f32 {
+    mesh.indices
+        .chunks(3)
+        .map(|face| {
+            let [a, b, c] = [face[0], face[1], face[2]].map(|i| mesh.vertex(i));
+            triangle_distance(point, a, b, c)
+        })
+        .fold(f32::INFINITY, f32::min)
+}
+
+fn sub(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+///Distance from `p` to segment `a`, `b`.
+fn segment_distance(p: [f32; 3], a: [f32; 3], b: [f32; 3]) -> f32 {
+    let (ab, ap) = (sub(b, a), sub(p, a));
+    let t = if dot(ab, ab) > 0.0 {
+        (dot(ap, ab) / dot(ab, ab)).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    distance(p, [0, 1, 2].map(|k| a[k] + ab[k] * t))
+}
+
+///Distance from `p` to triangle `a`, `b`, `c`: the distance to its plane if `p` projects inside
+///the triangle, else the distance to the closest side.
+fn triangle_distance(p: [f32; 3], a: [f32; 3], b: [f32; 3], c: [f32; 3]) -> f32 {
+    let sides = segment_distance(p, a, b)
+        .min(segment_distance(p, b, c))
+        .min(segment_distance(p, c, a));
+    let normal = cross(sub(b, a), sub(c, a));
+    let length = dot(normal, normal).sqrt();
+    if length == 0.0 {
+        return sides;
+    }
+    // Inside if `p` lies on the inner side of the planes through every side along the normal.
+    let inside = [(a, b), (b, c), (c, a)]
+        .iter()
+        .all(|&(start, end)| dot(cross(sub(end, start), sub(p, start)), normal) >= 0.0);
+    if inside {
+        (dot(sub(p, a), normal) / length).abs()
+    } else {
+        sides
+    }
+}
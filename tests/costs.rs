@@ -0,0 +1,119 @@
+//! Paths minimizing cost over faces of different traversal costs.
+
+mod common;
+
+use common::*;
+use geodesic_path::{
+    compute_weighted_path, Mesh as PersistentMesh, PathOptions, PathResult, PathStatus,
+};
+
+fn weighted_path(mesh: &Mesh, start: usize, end: usize, costs: &[f32]) -> PathResult {
+    compute_weighted_path(
+        start,
+        end,
+        costs.to_vec(),
+        &tight_options(),
+        mesh.coordinates.clone(),
+        mesh.indices.clone(),
+    )
+}
+
+///Costs of the faces of `plane(n, 1.0)` from the position of their quad.
+fn plane_costs(n: usize, cost: impl Fn(usize, usize) -> f32) -> Vec<f32> {
+    (0..2 * n * n)
+        .map(|face| cost((face / 2) % n, face / 2 / n))
+        .collect()
+}
+
+#[test]
+fn uniform_costs_reproduce_paths() {
+    let mesh = sphere(32, 16);
+    for &(start, end) in [(3, 200), (0, 481)].iter() {
+        let expected = mesh.path(start, end);
+        for &cost in [1.0, 2.5].iter() {
+            let costs = vec![cost; mesh.indices.len() / 3];
+            let result = weighted_path(&mesh, start, end, &costs);
+            assert_eq!(result.positions(), expected.positions());
+            assert!((result.cost() - cost as f64 * expected.length()).abs() < 1e-5);
+        }
+        assert_eq!(expected.cost(), expected.length());
+    }
+}
+
+#[test]
+fn paths_refract_at_cost_boundaries() {
+    // The right half is three times as expensive.
+    let n = 10;
+    let mesh = plane(n, 1.0);
+    let costs = plane_costs(n, |x, _| if x < n / 2 { 1.0 } else { 3.0 });
+    // To (1, 0.7), so that the straight line misses the grid vertices.
+    let result = weighted_path(&mesh, 0, 7 * (n + 1) + n, &costs);
+    assert_eq!(result.status(), PathStatus::Converged);
+
+    // Crossing the boundary x = 0.5 at height y costs this much, minimal by Snell's law.
+    let cost = |y: f64| (0.25 + y * y).sqrt() + 3.0 * (0.25 + (0.7 - y).powi(2)).sqrt();
+    let expected = (0..=100000)
+        .map(|i| cost(0.7 * i as f64 / 100000.0))
+        .fold(f64::INFINITY, f64::min);
+    assert!(
+        (result.cost() - expected).abs() < 1e-4 * expected,
+        "cost {} expected {}",
+        result.cost(),
+        expected
+    );
+    // The straight diagonal is shorter but more expensive.
+    assert!(result.length() > 1.49f64.sqrt() + 1e-3);
+}
+
+#[test]
+fn paths_go_around_expensive_regions() {
+    // A swamp in the middle of the square, from 0.3 to 0.7 on both axes.
+    let n = 10;
+    let mesh = plane(n, 1.0);
+    let swamp = |x: usize, y: usize| (3..7).contains(&x) && (3..7).contains(&y);
+    let costs = plane_costs(n, |x, y| if swamp(x, y) { 20.0 } else { 1.0 });
+    let (start, end) = (5 * (n + 1), 5 * (n + 1) + n);
+    let result = weighted_path(&mesh, start, end, &costs);
+    assert_eq!(result.status(), PathStatus::Converged);
+    // Around a corner of the swamp: 2 * sqrt(0.3² + 0.2²) + 0.4.
+    let expected = 2.0 * (0.09f64 + 0.04).sqrt() + 0.4;
+    assert!((result.cost() - expected).abs() < 1e-4, "{}", result.cost());
+    for point in points(&result.positions()) {
+        let inside = |v: f32| v > 0.3 + 1e-4 && v < 0.7 - 1e-4;
+        assert!(!(inside(point[0]) && inside(point[1])), "{:?}", point);
+    }
+}
+
+#[test]
+fn paths_follow_vertex_cost_gradients() {
+    // Costs rise from 1 at the bottom to 10 at the top of the square.
+    let n = 10;
+    let mesh = plane(n, 1.0);
+    let persistent = PersistentMesh::new(mesh.coordinates.clone(), mesh.indices.clone());
+    let costs = (0..(n + 1) * (n + 1))
+        .map(|vertex| 1.0 + 9.0 * (vertex / (n + 1)) as f32 / n as f32)
+        .collect();
+    persistent.set_vertex_costs(costs);
+    let (start, end) = (5 * (n + 1), 5 * (n + 1) + n);
+    let result = persistent.compute_path(start, end, &tight_options());
+    assert_eq!(result.status(), PathStatus::Converged);
+
+    // The straight line at y = 0.5 costs 5.5 all along.
+    assert!(result.cost() < 5.5 - 0.1, "{}", result.cost());
+    // Bent towards the cheap bottom edge.
+    let points = points(&result.positions());
+    assert!(points.iter().all(|point| point[1] <= 0.5 + 1e-4));
+    assert!(points.iter().any(|point| point[1] < 0.4));
+}
+
+#[test]
+fn vertex_costs_are_interpolated_along_segments() {
+    // Costs rise from 1 on the left side of the square to 3 on the right.
+    let mesh = plane(1, 1.0);
+    let persistent = PersistentMesh::new(mesh.coordinates.clone(), mesh.indices.clone());
+    persistent.set_vertex_costs(vec![1.0, 3.0, 1.0, 3.0]);
+    for &(start, end) in [(0, 1), (3, 2), (0, 3)].iter() {
+        let result = persistent.compute_path(start, end, &PathOptions::new());
+        assert!((result.cost() - 2.0 * result.length()).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,52 @@
+//! Query statistics.
+
+mod common;
+
+use common::*;
+use geodesic_path::{set_debug, PathOptions};
+
+#[test]
+fn stats_count_the_work_of_a_query() {
+    let mesh = plane(6, 1.0);
+    let result = mesh.path(1, 47);
+    let stats = result.stats();
+    assert!(stats.iterations > 0);
+    assert_eq!(stats.iterations, result.iterations());
+    assert!(stats.wedges_unfolded > 0);
+    assert!(stats.dijkstra_time >= 0.0);
+    // Paths are only cut for path meshes.
+    assert_eq!(stats.cuts, 0);
+    assert_eq!(stats.sub_triangles, 0);
+
+    // Four diagonals and two sides of the grid squares.
+    let step = 1.0 / 6.0;
+    let edge_path = 4.0 * step * 2f64.sqrt() + 2.0 * step;
+    assert!((stats.dijkstra_length - edge_path).abs() < 1e-5);
+    assert_eq!(stats.dijkstra_length, result.dijkstra_length());
+    assert!(result.length() < stats.dijkstra_length);
+}
+
+#[test]
+fn stats_of_an_unshortened_path() {
+    let mesh = sphere(12, 8);
+    let mut options = PathOptions::new();
+    options.set_max_iterations(0);
+    let result = mesh.path_with_options(3, 70, &options);
+    let stats = result.stats();
+    assert_eq!(stats.iterations, 0);
+    assert_eq!(stats.wedges_unfolded, 0);
+    let edge_path = path_length(&points(&result.positions())) as f64;
+    assert!((stats.dijkstra_length - edge_path).abs() < 1e-5);
+}
+
+#[test]
+fn stats_do_not_depend_on_logging() {
+    let mesh = cube(4);
+    let quiet = mesh.path(0, 60).stats();
+    set_debug(true);
+    let logged = mesh.path(0, 60).stats();
+    set_debug(false);
+    assert_eq!(quiet.iterations, logged.iterations);
+    assert_eq!(quiet.wedges_unfolded, logged.wedges_unfolded);
+    assert_eq!(quiet.dijkstra_length, logged.dijkstra_length);
+}
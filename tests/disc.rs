@@ -0,0 +1,87 @@
+//! Surface within a geodesic radius of a point.
+
+mod common;
+
+use common::*;
+use geodesic_path::{geodesic_disc, GeodesicDisc};
+
+fn disc(mesh: &Mesh, face: usize, barycentric: [f32; 3], radius: f32) -> GeodesicDisc {
+    geodesic_disc(
+        face,
+        barycentric.to_vec(),
+        radius,
+        mesh.coordinates.clone(),
+        mesh.indices.clone(),
+    )
+}
+
+///Checks the disc of `radius` around `source` against the exact distances `expected`, which the
+///mesh may miss by `tolerance`.
+fn assert_disc(
+    mesh: &Mesh,
+    result: &GeodesicDisc,
+    radius: f32,
+    expected: impl Fn(usize) -> f32,
+    tolerance: f32,
+) {
+    let vertices = result.vertices();
+    for (&vertex, &distance) in vertices.iter().zip(result.distances().iter()) {
+        assert!(distance <= radius);
+        assert!((distance - expected(vertex)).abs() <= tolerance);
+    }
+    for vertex in 0..mesh.coordinates.len() / 3 {
+        if expected(vertex) < radius - tolerance {
+            assert!(vertices.contains(&vertex), "vertex {} is missing", vertex);
+        }
+    }
+    for face in result.faces() {
+        let corners = &mesh.indices[3 * face..3 * face + 3];
+        assert!(corners.iter().all(|vertex| vertices.contains(vertex)));
+    }
+    assert!(!result.partial_faces().is_empty());
+    assert_eq!(result.boundary().len() % 6, 0);
+}
+
+#[test]
+fn plane_discs_are_circles() {
+    let mesh = plane(10, 1.0);
+    let face = 2 * (5 * 10 + 5);
+    let result = disc(&mesh, face, [1.0, 0.0, 0.0], 0.3);
+    let center = mesh.vertex(mesh.indices[3 * face]);
+    assert_disc(
+        &mesh,
+        &result,
+        0.3,
+        |x| distance(center, mesh.vertex(x)),
+        1e-5,
+    );
+    assert_eq!(result.vertices()[0], mesh.indices[3 * face]);
+    // Every boundary point lies on the circle.
+    for point in points(&result.boundary()) {
+        assert!((distance(point, center) - 0.3).abs() < 0.02);
+    }
+}
+
+#[test]
+fn sphere_discs_follow_great_circles() {
+    let mesh = sphere(32, 16);
+    // The north pole is the first corner of face 0.
+    let result = disc(&mesh, 0, [1.0, 0.0, 0.0], 1.0);
+    let polar_angle = |vertex: usize| mesh.vertex(vertex)[2].clamp(-1.0, 1.0).acos();
+    assert_disc(&mesh, &result, 1.0, polar_angle, 0.01);
+    assert!(result.vertices().len() > 32 * 4);
+}
+
+#[test]
+fn bad_input_gives_empty_discs() {
+    let mesh = plane(2, 1.0);
+    assert!(disc(&mesh, 100, [1.0, 0.0, 0.0], 1.0).vertices().is_empty());
+    let result = geodesic_disc(
+        0,
+        vec![1.0, 0.0],
+        1.0,
+        mesh.coordinates.clone(),
+        mesh.indices.clone(),
+    );
+    assert!(result.vertices().is_empty());
+}
@@ -0,0 +1,87 @@
+//! Distance fields of several sources, checked on the plane and against path queries.
+
+mod common;
+
+use common::*;
+use geodesic_path::{distance_field, distance_field_from_points};
+
+#[test]
+fn plane_fields_label_the_nearest_source() {
+    let n = 12;
+    let mesh = plane(n, 1.0);
+    let sources = [0, n, 7 * (n + 1) + 5];
+    let field = distance_field(
+        sources.to_vec(),
+        mesh.coordinates.clone(),
+        mesh.indices.clone(),
+    );
+    let (distances, labels) = (field.distances(), field.sources());
+    for vertex in 0..(n + 1) * (n + 1) {
+        let mut expected: Vec<(f32, usize)> = sources
+            .iter()
+            .enumerate()
+            .map(|(label, &source)| (distance(mesh.vertex(vertex), mesh.vertex(source)), label))
+            .collect();
+        expected.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert!((distances[vertex] - expected[0].0).abs() < 1e-4);
+        if expected[1].0 - expected[0].0 > 1e-3 {
+            assert_eq!(labels[vertex], expected[0].1 as i32);
+        }
+    }
+}
+
+#[test]
+fn sphere_fields_follow_path_lengths() {
+    let mesh = sphere(32, 16);
+    let field = distance_field(vec![3, 300], mesh.coordinates.clone(), mesh.indices.clone());
+    for &vertex in [40, 100, 200, 250, 400, 481].iter() {
+        let label = field.sources()[vertex];
+        let source = [3, 300][label as usize];
+        let length = path_length(&points(&mesh.path(source, vertex).positions()));
+        let found = field.distances()[vertex];
+        assert!(
+            (found - length).abs() < 0.02 * length,
+            "{} {}",
+            found,
+            length
+        );
+        let other = [3, 300][1 - label as usize];
+        let other_length = path_length(&points(&mesh.path(other, vertex).positions()));
+        assert!(length <= other_length * 1.02);
+    }
+}
+
+#[test]
+fn point_sources_on_vertices_match_vertex_sources() {
+    let mesh = cube(4);
+    // Face 0 starts at vertex 0, face 10 at its first index.
+    let faces = vec![0, 10];
+    let barycentric = vec![1.0, 0.0, 0.0, 1.0, 0.0, 0.0];
+    let sources = vec![mesh.indices[0], mesh.indices[30]];
+    let from_points = distance_field_from_points(
+        faces,
+        barycentric,
+        mesh.coordinates.clone(),
+        mesh.indices.clone(),
+    );
+    let from_vertices = distance_field(sources, mesh.coordinates.clone(), mesh.indices.clone());
+    for (a, b) in from_points
+        .distances()
+        .iter()
+        .zip(from_vertices.distances().iter())
+    {
+        assert!((a - b).abs() < 1e-5);
+    }
+}
+
+#[test]
+fn unconnected_vertices_have_no_source() {
+    let mut mesh = plane(2, 1.0);
+    mesh.coordinates
+        .extend_from_slice(&[5., 5., 0., 6., 5., 0., 5., 6., 0.]);
+    mesh.indices.extend_from_slice(&[9, 10, 11]);
+    let field = distance_field(vec![0, 100], mesh.coordinates, mesh.indices);
+    assert_eq!(field.sources()[10], -1);
+    assert_eq!(field.distances()[10], f32::INFINITY);
+    assert_eq!(field.sources()[8], 0);
+}
@@ -0,0 +1,73 @@
+//! Distance matrices between landmarks, checked against single path queries.
+
+mod common;
+
+use common::*;
+use geodesic_path::{distance_matrix, DistanceMethod, PathStatus};
+
+fn matrix(mesh: &Mesh, landmarks: &[usize], method: DistanceMethod) -> Vec<f32> {
+    distance_matrix(
+        landmarks.to_vec(),
+        method,
+        &tight_options(),
+        mesh.coordinates.clone(),
+        mesh.indices.clone(),
+    )
+}
+
+#[test]
+fn matrices_match_path_queries() {
+    let mesh = sphere(32, 16);
+    let landmarks = [0, 3, 100, 200, 333, 481];
+    let count = landmarks.len();
+    let edges = matrix(&mesh, &landmarks, DistanceMethod::Edges);
+    let shortened = matrix(&mesh, &landmarks, DistanceMethod::Shortened);
+    for i in 0..count {
+        assert_eq!(edges[i * count + i], 0.0);
+        assert_eq!(shortened[i * count + i], 0.0);
+        for j in 0..count {
+            assert_eq!(edges[i * count + j], edges[j * count + i]);
+            assert_eq!(shortened[i * count + j], shortened[j * count + i]);
+        }
+        for j in i + 1..count {
+            let result = mesh.path(landmarks[i], landmarks[j]);
+            assert_eq!(result.status(), PathStatus::Converged);
+            let edge = edges[i * count + j] as f64;
+            assert!((edge - result.dijkstra_length()).abs() < 1e-4);
+            let length = shortened[i * count + j] as f64;
+            assert!(length <= edge * (1.0 + 1e-6));
+            // Dijkstra ties may start shortening from another edge path.
+            assert!((length - result.length()).abs() < 1e-3 * result.length());
+        }
+    }
+}
+
+#[test]
+fn plane_distances_are_straight() {
+    let n = 10;
+    let mesh = plane(n, 1.0);
+    let landmarks = [0, n, 60, n * (n + 1), 120];
+    let shortened = matrix(&mesh, &landmarks, DistanceMethod::Shortened);
+    for (i, &a) in landmarks.iter().enumerate() {
+        for (j, &b) in landmarks.iter().enumerate() {
+            let expected = distance(mesh.vertex(a), mesh.vertex(b));
+            let found = shortened[i * landmarks.len() + j];
+            assert!((found - expected).abs() < 1e-4, "{} {}", found, expected);
+        }
+    }
+}
+
+#[test]
+fn unconnected_and_invalid_landmarks_are_infinite() {
+    let mut mesh = plane(2, 1.0);
+    // A separate triangle.
+    mesh.coordinates
+        .extend_from_slice(&[5., 5., 0., 6., 5., 0., 5., 6., 0.]);
+    mesh.indices.extend_from_slice(&[9, 10, 11]);
+    let found = matrix(&mesh, &[0, 9, 100, 0], DistanceMethod::Edges);
+    assert_eq!(found[1], f32::INFINITY);
+    assert_eq!(found[2], f32::INFINITY);
+    assert_eq!(found[3], 0.0);
+    assert_eq!(found[2 * 4 + 2], 0.0);
+    assert_eq!(matrix(&mesh, &[4, 4], DistanceMethod::Edges), vec![0.0; 4]);
+}
@@ -0,0 +1,80 @@
+//! Log and exp maps around a surface vertex.
+
+mod common;
+
+use common::*;
+use geodesic_path::{exp_map, log_map};
+
+///Checks that the exp map of the log map of every vertex within `radius` of `source` gives back
+///the vertex.
+fn assert_round_trip(mesh: &Mesh, source: usize, radius: f32) {
+    let chart = log_map(source, mesh.coordinates.clone(), mesh.indices.clone());
+    let mut checked = 0;
+    for (vertex, polar) in chart.chunks(2).enumerate() {
+        let (distance, angle) = (polar[0], polar[1]);
+        if distance.is_nan() || distance > radius {
+            continue;
+        }
+        let vector = vec![distance * angle.cos(), distance * angle.sin()];
+        let point = exp_map(
+            source,
+            vector,
+            mesh.coordinates.clone(),
+            mesh.indices.clone(),
+        )
+        .unwrap_or_else(|| panic!("vertex {} at {:?} maps nowhere", vertex, polar));
+        let position = point.position();
+        let error = distance_between(&position, mesh.vertex(vertex));
+        assert!(error < 1e-4, "vertex {} maps to {:?}", vertex, position);
+        checked += 1;
+    }
+    assert!(checked > 10);
+}
+
+fn distance_between(a: &[f32], b: [f32; 3]) -> f32 {
+    distance([a[0], a[1], a[2]], b)
+}
+
+#[test]
+fn plane_charts_are_the_plane() {
+    let mesh = plane(8, 1.0);
+    let source = 4 * 9 + 4;
+    let chart = log_map(source, mesh.coordinates.clone(), mesh.indices.clone());
+    for (vertex, polar) in chart.chunks(2).enumerate() {
+        let expected = distance(mesh.vertex(source), mesh.vertex(vertex));
+        assert!((polar[0] - expected).abs() < 1e-5);
+    }
+    assert_round_trip(&mesh, source, 0.6);
+}
+
+#[test]
+fn cylinder_charts_unroll_the_cylinder() {
+    let mesh = cylinder(24, 8, 1.0, 2.0);
+    let source = 4 * 24;
+    // Below half the circumference geodesics don't meet around the back.
+    assert_round_trip(&mesh, source, 1.5);
+    let chart = log_map(source, mesh.coordinates.clone(), mesh.indices.clone());
+    // The vertex above the source is straight up the cylinder.
+    let above = 5 * 24;
+    assert!((chart[2 * above] - 0.25).abs() < 1e-5);
+}
+
+#[test]
+fn bad_input_gives_nothing() {
+    let mesh = plane(4, 1.0);
+    let (coordinates, indices) = (mesh.coordinates.clone(), mesh.indices.clone());
+    assert!(exp_map(0, vec![0.1], coordinates.clone(), indices.clone()).is_none());
+    assert!(exp_map(100, vec![0.1, 0.1], coordinates.clone(), indices.clone()).is_none());
+    let chart = log_map(100, coordinates, indices);
+    assert_eq!(chart.len(), 50);
+    assert!(chart.iter().all(|x| x.is_nan()));
+}
+
+#[test]
+fn invalid_faces_are_skipped() {
+    let square = vec![0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0, 0.0];
+    let indices = vec![0, 1, 2, 0, 0, 9, 0, 2, 3];
+    let point = exp_map(0, vec![0.5, 0.2], square, indices).unwrap();
+    assert_eq!(point.face, 0);
+    assert!(distance_between(&point.position(), [0.5, 0.2, 0.0]) < 1e-5);
+}
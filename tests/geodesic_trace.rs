@@ -0,0 +1,100 @@
+//! Straightest geodesics traced from a surface point.
+
+mod common;
+
+use common::*;
+use geodesic_path::{trace_geodesic, GeodesicTrace};
+
+fn trace(mesh: &Mesh, face: usize, direction: [f32; 3], length: f32) -> Option<GeodesicTrace> {
+    trace_geodesic(
+        face,
+        vec![1.0 / 3.0; 3],
+        direction.to_vec(),
+        length,
+        mesh.coordinates.clone(),
+        mesh.indices.clone(),
+    )
+}
+
+///Centroid of face `face`.
+fn centroid(mesh: &Mesh, face: usize) -> [f32; 3] {
+    let corners = [0, 1, 2].map(|i| mesh.vertex(mesh.indices[3 * face + i]));
+    [0, 1, 2].map(|k| corners.iter().map(|corner| corner[k]).sum::<f32>() / 3.0)
+}
+
+fn end_position(trace: &GeodesicTrace) -> [f32; 3] {
+    let position = trace.end().position();
+    [position[0], position[1], position[2]]
+}
+
+#[test]
+fn plane_traces_are_straight() {
+    let mesh = plane(8, 1.0);
+    let face = 2 * (3 * 8 + 3);
+    let start = centroid(&mesh, face);
+    let direction = [0.6, 0.8, 0.0];
+    let result = trace(&mesh, face, direction, 0.4).unwrap();
+    assert!((result.length() - 0.4).abs() < 1e-5);
+    let expected = [0, 1, 2].map(|k| start[k] + direction[k] * 0.4);
+    assert!(distance(end_position(&result), expected) < 1e-5);
+    let direction_out = result.direction();
+    assert!((direction_out[0] - 0.6).abs() < 1e-4 && (direction_out[1] - 0.8).abs() < 1e-4);
+    // Every crossing lies on the line.
+    for point in points(&result.points()) {
+        let offset = [0, 1].map(|k| point[k] - start[k]);
+        assert!((offset[0] * 0.8 - offset[1] * 0.6).abs() < 1e-5);
+    }
+    assert!(result.faces().len() > 2);
+}
+
+#[test]
+fn cylinder_traces_unroll_to_lines() {
+    let mesh = cylinder(24, 8, 1.0, 2.0);
+    let face = 2 * (2 * 24 + 5);
+    let start = centroid(&mesh, face);
+    // Straight up along the axis.
+    let result = trace(&mesh, face, [0.0, 0.0, 1.0], 1.0).unwrap();
+    let end = end_position(&result);
+    assert!(distance(end, [start[0], start[1], start[2] + 1.0]) < 1e-4);
+
+    // Around the cylinder the trace stays at its height and comes back after a full turn.
+    let side = distance(mesh.vertex(0), mesh.vertex(1));
+    let tangent = [-start[1], start[0], 0.0];
+    let result = trace(&mesh, face, tangent, 24.0 * side).unwrap();
+    assert!((result.length() - 24.0 * side).abs() < 1e-4);
+    assert!(distance(end_position(&result), start) < 1e-4);
+    assert!(points(&result.points())
+        .iter()
+        .all(|point| (point[2] - start[2]).abs() < 1e-4));
+}
+
+#[test]
+fn traces_stop_at_the_boundary() {
+    let mesh = plane(8, 1.0);
+    let face = 2 * (3 * 8 + 3);
+    let start = centroid(&mesh, face);
+    let result = trace(&mesh, face, [1.0, 0.0, 0.0], 5.0).unwrap();
+    assert!((result.length() - (1.0 - start[0])).abs() < 1e-5);
+    let end = end_position(&result);
+    assert!((end[0] - 1.0).abs() < 1e-5 && (end[1] - start[1]).abs() < 1e-5);
+}
+
+#[test]
+fn bad_input_gives_nothing() {
+    let mesh = plane(2, 1.0);
+    let (coordinates, indices) = (mesh.coordinates.clone(), mesh.indices.clone());
+    let trace = |face, barycentric: Vec<f32>, direction: Vec<f32>| {
+        trace_geodesic(
+            face,
+            barycentric,
+            direction,
+            1.0,
+            coordinates.clone(),
+            indices.clone(),
+        )
+    };
+    assert!(trace(100, vec![0.5, 0.5, 0.0], vec![1.0, 0.0, 0.0]).is_none());
+    assert!(trace(0, vec![0.5, 0.5], vec![1.0, 0.0, 0.0]).is_none());
+    assert!(trace(0, vec![0.5, 0.5, 0.0], vec![1.0]).is_none());
+    assert!(trace(0, vec![0.5, 0.5, 0.0], vec![0.0, 0.0, 1.0]).is_none());
+}
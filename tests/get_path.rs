@@ -0,0 +1,93 @@
+//! The plain `get_path` export. Shortening keeps path points on input edges and straightens a
+//! wedge by running the funnel algorithm across every face between the neighbouring path
+//! vertices, instead of cutting the faces of the wedge into sub triangles.
+
+mod common;
+
+use common::*;
+use geodesic_path::{get_path, PathOptions};
+
+fn path(mesh: &Mesh, start: usize, end: usize) -> Vec<[f32; 3]> {
+    points(&get_path(
+        start,
+        end,
+        mesh.coordinates.clone(),
+        mesh.indices.clone(),
+    ))
+}
+
+///Checks that the path runs between `start` and `end` and that every point lies on an edge of
+///`mesh`, next to the previous point on a face.
+fn assert_on_edges(mesh: &Mesh, path: &[[f32; 3]], start: usize, end: usize) {
+    assert!(distance(path[0], mesh.vertex(start)) < 1e-5);
+    assert!(distance(path[path.len() - 1], mesh.vertex(end)) < 1e-5);
+    let on_edge = |point: [f32; 3], a: [f32; 3], b: [f32; 3]| {
+        distance(a, point) + distance(point, b) - distance(a, b) < 1e-5
+    };
+    let faces: Vec<[[f32; 3]; 3]> = mesh
+        .indices
+        .chunks(3)
+        .map(|face| [face[0], face[1], face[2]].map(|i| mesh.vertex(i)))
+        .collect();
+    let on_face = |point: [f32; 3], face: &[[f32; 3]; 3]| {
+        (0..3).any(|i| on_edge(point, face[i], face[(i + 1) % 3]))
+    };
+    for pair in path.windows(2) {
+        assert!(
+            faces
+                .iter()
+                .any(|face| on_face(pair[0], face) && on_face(pair[1], face)),
+            "{:?}",
+            pair
+        );
+    }
+}
+
+#[test]
+fn plane_paths_are_straight() {
+    let mesh = plane(6, 1.0);
+    // The default angle tolerance leaves slight bends at some vertices.
+    for &(start, end, tolerance) in [(1, 47, 1e-4), (0, 48, 1e-4), (6, 43, 0.01)].iter() {
+        let points = path(&mesh, start, end);
+        assert_on_edges(&mesh, &points, start, end);
+        let straight = distance(mesh.vertex(start), mesh.vertex(end));
+        let length = path_length(&points);
+        assert!(length >= straight * (1.0 - 1e-5));
+        assert!(
+            length <= straight * (1.0 + tolerance),
+            "{} {}",
+            length,
+            straight
+        );
+    }
+}
+
+#[test]
+fn paths_are_no_longer_than_edge_paths() {
+    // The cut based shortening panicked on wedges that left the unfolded fan.
+    let options = PathOptions::new();
+    for (mesh, start, end) in [
+        (sphere(12, 8), 3, 70),
+        (cylinder(8, 4, 1.0, 2.0), 0, 36),
+        (cone(12, 6, 1.0, 1.0), 1, 67),
+        (cube(4), 0, 60),
+    ] {
+        let points = path(&mesh, start, end);
+        assert_on_edges(&mesh, &points, start, end);
+        let result = mesh.path_with_options(start, end, &options);
+        assert!((path_length(&points) - result.length() as f32).abs() < 1e-5);
+        assert!(result.length() <= result.dijkstra_length() * (1.0 + 1e-6));
+    }
+}
+
+#[test]
+fn path_around_a_cylinder() {
+    // Half way around the prism, so that the shortest paths run across the seam of the fan.
+    let mesh = cylinder(8, 4, 1.0, 2.0);
+    let points = path(&mesh, 0, 4 * 8 + 4);
+    assert_on_edges(&mesh, &points, 0, 36);
+    let side = distance(mesh.vertex(0), mesh.vertex(1));
+    let unrolled = ((4.0 * side).powi(2) + 4.0).sqrt();
+    assert!(path_length(&points) >= unrolled * (1.0 - 1e-4));
+    assert!(path_length(&points) <= 4.0 * side + 2.0);
+}
@@ -0,0 +1,68 @@
+//! Terrain surfaces built from grids of heights.
+
+mod common;
+
+use common::*;
+use geodesic_path::{Mesh as PersistentMesh, PathStatus};
+
+#[test]
+fn sloped_grids_give_straight_paths() {
+    // 11 by 6 grid of 10 m cells rising 0.5 m per metre along x.
+    let heights: Vec<f32> = (0..66).map(|i| (i % 11) as f32 * 5.0).collect();
+    let mesh = PersistentMesh::from_heightfield(&heights, 11, 10.0, 1000.0, 2000.0);
+    let result = mesh.compute_path(0, 65, &tight_options());
+    assert_eq!(result.status(), PathStatus::Converged);
+    let positions = points(&result.positions());
+    assert_eq!(positions[0], [1000.0, 2000.0, 0.0]);
+    assert_eq!(positions[positions.len() - 1], [1100.0, 2050.0, 50.0]);
+    let expected = (100.0f64 * 100.0 + 50.0 * 50.0 + 50.0 * 50.0).sqrt();
+    assert!((result.length() - expected).abs() < 1e-3);
+}
+
+#[test]
+fn cells_are_split_along_the_flatter_diagonal() {
+    // A single cell with one corner raised is split between the low corners, so the path to
+    // the raised corner crosses the fold.
+    let mesh = PersistentMesh::from_heightfield(&[0.0, 0.0, 0.0, 1.0], 2, 1.0, 0.0, 0.0);
+    let result = mesh.compute_path(0, 3, &tight_options());
+    let expected = 0.5f64.sqrt() + 1.5f64.sqrt();
+    assert!((result.length() - expected).abs() < 1e-4);
+
+    let result = mesh.compute_path(1, 2, &tight_options());
+    assert!((result.length() - 2f64.sqrt()).abs() < 1e-5);
+}
+
+#[test]
+fn no_data_heights_leave_holes() {
+    let mut heights = vec![0.0; 25];
+    // A wall of no data across the middle row.
+    for height in heights[10..15].iter_mut() {
+        *height = f32::NAN;
+    }
+    let mesh = PersistentMesh::from_heightfield(&heights, 5, 1.0, 0.0, 0.0);
+    assert_eq!(
+        mesh.compute_path(0, 24, &tight_options()).status(),
+        PathStatus::NoPath
+    );
+    let result = mesh.compute_path(0, 9, &tight_options());
+    assert!((result.length() - (16.0f64 + 1.0).sqrt()).abs() < 1e-5);
+
+    // Heights past the last full row are ignored.
+    let mesh = PersistentMesh::from_heightfield(&[0.0; 7], 3, 1.0, 0.0, 0.0);
+    let result = mesh.compute_path(0, 5, &tight_options());
+    assert!((result.length() - 5f64.sqrt()).abs() < 1e-5);
+    assert_eq!(mesh.get_path(0, 6), Vec::<f32>::new());
+}
+
+#[test]
+fn cells_keep_the_triangle_without_a_no_data_corner() {
+    // Corners of the cell are 0 and 1 at the bottom, 2 and 3 at the top.
+    for &(missing, start, end) in [(0, 1, 2), (1, 0, 3), (2, 0, 3), (3, 1, 2)].iter() {
+        let mut heights = [0.0; 4];
+        heights[missing] = f32::NAN;
+        let mesh = PersistentMesh::from_heightfield(&heights, 2, 1.0, 0.0, 0.0);
+        let result = mesh.compute_path(start, end, &tight_options());
+        assert_eq!(result.status(), PathStatus::Converged, "{}", missing);
+        assert!((result.length() - 2f64.sqrt()).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,67 @@
+//! Iteration and time limits of path shortening. Cancellation callbacks are tested in the
+//! browser suite.
+
+mod common;
+
+use common::*;
+use geodesic_path::{PathOptions, PathStatus};
+
+fn options(max_iterations: usize, time_limit: Option<f64>) -> PathOptions {
+    let mut options = PathOptions::new();
+    options.set_max_iterations(max_iterations);
+    options.set_time_limit(time_limit);
+    options
+}
+
+#[test]
+fn iteration_limit_truncates_the_path() {
+    let mesh = sphere(16, 12);
+    let converged = mesh.path_with_options(3, 150, &options(usize::MAX, None));
+    assert_eq!(converged.status(), PathStatus::Converged);
+    let iterations = converged.iterations();
+    assert!(iterations > 2);
+
+    let mut previous = f64::INFINITY;
+    for limit in 0..iterations {
+        let result = mesh.path_with_options(3, 150, &options(limit, None));
+        assert_eq!(result.status(), PathStatus::IterationLimit);
+        assert_eq!(result.iterations(), limit);
+        // Every iteration shortens the path, so a truncated path is the best one found.
+        assert!(result.length() <= previous);
+        assert!(result.length() >= converged.length());
+        previous = result.length();
+    }
+    let result = mesh.path_with_options(3, 150, &options(iterations, None));
+    assert_eq!(result.status(), PathStatus::Converged);
+    assert_eq!(result.length(), converged.length());
+}
+
+#[test]
+fn time_limit_truncates_the_path() {
+    let mesh = sphere(16, 12);
+    let result = mesh.path_with_options(3, 150, &options(usize::MAX, Some(0.0)));
+    assert_eq!(result.status(), PathStatus::TimeLimit);
+    assert_eq!(result.iterations(), 0);
+    assert_eq!(result.length(), result.dijkstra_length());
+
+    let result = mesh.path_with_options(3, 150, &options(usize::MAX, Some(10000.0)));
+    assert_eq!(result.status(), PathStatus::Converged);
+    assert!(result.length() < result.dijkstra_length());
+}
+
+#[test]
+fn iteration_limit_comes_before_time_limit() {
+    let mesh = plane(4, 1.0);
+    let result = mesh.path_with_options(0, 24, &options(0, Some(0.0)));
+    assert_eq!(result.status(), PathStatus::IterationLimit);
+}
+
+#[test]
+fn unconnected_vertices_have_no_path() {
+    let mut mesh = plane(2, 1.0);
+    // A lone vertex outside the grid.
+    mesh.coordinates.extend_from_slice(&[5.0, 5.0, 0.0]);
+    let result = mesh.path_with_options(0, 9, &options(usize::MAX, None));
+    assert_eq!(result.status(), PathStatus::NoPath);
+    assert!(result.positions().is_empty());
+}
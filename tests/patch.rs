@@ -0,0 +1,77 @@
+//! Paths restricted to a patch of faces.
+
+mod common;
+
+use common::*;
+use geodesic_path::{Mesh as PersistentMesh, PathOptions, PathStatus};
+
+///Faces of the quads of `plane(10, 1.0)` in the L shape left of x = 0.3 or below y = 0.3.
+fn l_shape() -> Vec<usize> {
+    (0..200)
+        .filter(|face| face / 2 % 10 < 3 || face / 20 < 3)
+        .collect()
+}
+
+fn options(patch: Option<Vec<usize>>, blocked: Vec<usize>) -> PathOptions {
+    let mut options = tight_options();
+    options.set_patch(patch);
+    options.set_blocked_faces(blocked);
+    options
+}
+
+#[test]
+fn paths_stay_on_the_patch() {
+    let mesh = plane(10, 1.0);
+    let result = mesh.path_with_options(10, 110, &options(Some(l_shape()), vec![]));
+    assert_eq!(result.status(), PathStatus::Converged);
+    for point in points(&result.positions()) {
+        assert!(
+            point[0] < 0.3 + 1e-4 || point[1] < 0.3 + 1e-4,
+            "{:?}",
+            point
+        );
+    }
+    // Straight to the inner corner and on to the end.
+    let expected = 2.0 * (0.7f64 * 0.7 + 0.3 * 0.3).sqrt();
+    assert!((result.length() - expected).abs() < 1e-4);
+
+    // The patch acts like blocking every other face.
+    let outside = (0..200).filter(|face| !l_shape().contains(face)).collect();
+    let blocked = mesh.path_with_options(10, 110, &options(None, outside));
+    assert_eq!(result.positions(), blocked.positions());
+}
+
+#[test]
+fn patches_combine_with_blocked_faces() {
+    let mesh = plane(10, 1.0);
+    // Blocking the second row of quads cuts the patch in two.
+    let strip = (0..200).filter(|face| face / 20 == 1).collect();
+    let result = mesh.path_with_options(10, 110, &options(Some(l_shape()), strip));
+    assert_eq!(result.status(), PathStatus::NoPath);
+
+    // Ends outside the patch have no path.
+    let result = mesh.path_with_options(10, 120, &options(Some(l_shape()), vec![]));
+    assert_eq!(result.status(), PathStatus::NoPath);
+
+    let result = mesh.path_with_options(10, 110, &options(Some(vec![]), vec![]));
+    assert_eq!(result.status(), PathStatus::NoPath);
+}
+
+#[test]
+fn interleaved_queries_keep_their_own_patches() {
+    let mesh = plane(10, 1.0);
+    let persistent = PersistentMesh::new(mesh.coordinates.clone(), mesh.indices.clone());
+    let (patched, free) = (options(Some(l_shape()), vec![]), options(None, vec![]));
+    let expected = persistent.compute_path(10, 110, &patched);
+
+    let mut around = persistent.query(10, 110, &patched);
+    // Started after the patched query, on the whole mesh.
+    let mut straight = persistent.query(10, 110, &free);
+    let (mut around_done, mut straight_done) = (false, false);
+    while !(around_done && straight_done) {
+        around_done = around.step(1);
+        straight_done = straight.step(1);
+    }
+    assert_eq!(around.result().positions(), expected.positions());
+    assert!((straight.result().length() - 2f64.sqrt()).abs() < 1e-5);
+}
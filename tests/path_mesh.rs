@@ -0,0 +1,135 @@
+//! Meshes cut along computed paths.
+
+mod common;
+
+use common::*;
+use geodesic_path::{get_path_mesh, PathMesh};
+use std::collections::HashMap;
+
+fn path_mesh(mesh: &Mesh, start: usize, end: usize) -> PathMesh {
+    get_path_mesh(start, end, mesh.coordinates.clone(), mesh.indices.clone())
+}
+
+fn edge(a: usize, b: usize) -> (usize, usize) {
+    (a.min(b), a.max(b))
+}
+
+///Counts the faces of every undirected edge.
+fn edge_faces(indices: &[usize]) -> HashMap<(usize, usize), usize> {
+    let mut result = HashMap::new();
+    for face in indices.chunks(3) {
+        for i in 0..3 {
+            *result.entry(edge(face[i], face[(i + 1) % 3])).or_insert(0) += 1;
+        }
+    }
+    result
+}
+
+fn area(a: [f32; 3], b: [f32; 3], c: [f32; 3]) -> f32 {
+    let (u, v) = (
+        [0, 1, 2].map(|k| b[k] - a[k]),
+        [0, 1, 2].map(|k| c[k] - a[k]),
+    );
+    let cross = [
+        u[1] * v[2] - u[2] * v[1],
+        u[2] * v[0] - u[0] * v[2],
+        u[0] * v[1] - u[1] * v[0],
+    ];
+    distance(cross, [0.0; 3]) / 2.0
+}
+
+fn total_area(positions: &[[f32; 3]], indices: &[usize]) -> f32 {
+    indices
+        .chunks(3)
+        .map(|face| area(positions[face[0]], positions[face[1]], positions[face[2]]))
+        .sum()
+}
+
+///Checks that the cut mesh covers the input, that no vertex lies inside an edge of another
+///triangle and that the path runs along its edges.
+fn check(mesh: &Mesh, result: &PathMesh) {
+    let positions = points(&result.positions());
+    let indices = result.indices();
+    let input = points(&mesh.coordinates);
+    for (position, input) in positions.iter().zip(input.iter()) {
+        assert!(distance(*position, *input) < 1e-5);
+    }
+    let (before, after) = (
+        total_area(&input, &mesh.indices),
+        total_area(&positions, &indices),
+    );
+    assert!(
+        (before - after).abs() < 1e-4 * before,
+        "{} {}",
+        before,
+        after
+    );
+
+    // Edges shared by two input faces stay shared by two triangles.
+    let input_edges = edge_faces(&mesh.indices);
+    let edges = edge_faces(&indices);
+    assert!(edges.values().all(|&count| count <= 2));
+    let boundary = edges.values().filter(|&&count| count == 1).count();
+    let input_boundary = input_edges.values().filter(|&&count| count == 1).count();
+    if input_boundary == 0 {
+        assert_eq!(boundary, 0);
+    }
+
+    // T-junctions.
+    for &(a, b) in edges.keys() {
+        let (start, end) = (positions[a], positions[b]);
+        let length = distance(start, end);
+        for (index, &point) in positions.iter().enumerate() {
+            if index == a || index == b {
+                continue;
+            }
+            let inside = distance(start, point) + distance(point, end) - length;
+            assert!(inside > 1e-5, "vertex {} inside edge {:?}", index, (a, b));
+        }
+    }
+
+    let path = result.path();
+    assert!(path.len() >= 2);
+    for pair in path.windows(2) {
+        assert!(edges.contains_key(&edge(pair[0], pair[1])), "{:?}", pair);
+    }
+}
+
+#[test]
+fn plane_cut_is_conforming() {
+    let mesh = plane(6, 1.0);
+    // Across the diagonals, so that every crossed face is cut.
+    let result = path_mesh(&mesh, 1, 47);
+    check(&mesh, &result);
+    assert!(result.stats().cuts > 0);
+    let path: Vec<[f32; 3]> = result
+        .path()
+        .iter()
+        .map(|&i| points(&result.positions())[i])
+        .collect();
+    assert!((path_length(&path) - distance(mesh.vertex(1), mesh.vertex(47))).abs() < 1e-4);
+}
+
+#[test]
+fn closed_mesh_cut_is_conforming() {
+    let mesh = sphere(10, 8);
+    let result = path_mesh(&mesh, 12, 55);
+    check(&mesh, &result);
+    assert!(result.stats().cuts > 0);
+}
+
+#[test]
+fn cylinder_cut_is_conforming() {
+    let mesh = cylinder(8, 4, 1.0, 2.0);
+    let result = path_mesh(&mesh, 0, 4 * 8 + 3);
+    check(&mesh, &result);
+}
+
+#[test]
+fn path_along_edges_cuts_nothing() {
+    let mesh = plane(4, 1.0);
+    let result = path_mesh(&mesh, 0, 4);
+    check(&mesh, &result);
+    assert_eq!(result.stats().cuts, 0);
+    assert_eq!(result.indices(), mesh.indices);
+}
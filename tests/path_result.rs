@@ -0,0 +1,56 @@
+//! Certificates of computed paths.
+
+mod common;
+
+use common::*;
+use geodesic_path::{PathOptions, PathStatus};
+use std::f32::consts::PI;
+
+#[test]
+fn converged_paths_are_geodesic_within_their_tolerance() {
+    for (mesh, start, end) in [
+        (plane(6, 1.0), 1, 47),
+        (sphere(12, 8), 3, 70),
+        (cube(4), 0, 60),
+    ] {
+        for tolerance in [PathOptions::new().angle_tolerance(), 0.05, 0.001] {
+            let mut options = PathOptions::new();
+            options.set_angle_tolerance(tolerance);
+            let result = mesh.path_with_options(start, end, &options);
+            assert_eq!(result.status(), PathStatus::Converged);
+            assert!(result.geodesic(), "min angles {:?}", result.min_angles());
+            for angle in result.min_angles() {
+                assert!(angle >= PI - tolerance);
+            }
+        }
+    }
+}
+
+#[test]
+fn edge_paths_are_not_geodesic() {
+    let mesh = plane(6, 1.0);
+    let mut options = PathOptions::new();
+    options.set_max_iterations(0);
+    let result = mesh.path_with_options(1, 47, &options);
+    assert_eq!(result.status(), PathStatus::IterationLimit);
+    assert_eq!(result.length(), result.dijkstra_length());
+    assert!(!result.geodesic());
+    assert!(result.min_angles().iter().any(|angle| *angle < PI - 0.2));
+}
+
+#[test]
+fn joints_have_angles_on_both_sides() {
+    let mesh = cube(4);
+    let result = mesh.path(0, 60);
+    let joints = result.joints();
+    assert!(!joints.is_empty());
+    assert_eq!(result.left_angles().len(), joints.len());
+    assert_eq!(result.right_angles().len(), joints.len());
+    let positions = points(&result.positions());
+    for (i, &joint) in joints.iter().enumerate() {
+        assert!(joint > 0 && joint < positions.len() - 1);
+        // Angle sums around a vertex of the closed cube add up to its cone angle.
+        let total = result.left_angles()[i] + result.right_angles()[i];
+        assert!(total > PI && total <= 2.0 * PI + 1e-4, "{}", total);
+    }
+}
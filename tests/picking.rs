@@ -0,0 +1,151 @@
+//! Ray picking and nearest surface points through the face hierarchy of `Mesh`, checked
+//! against brute force over all faces.
+
+mod common;
+
+use common::*;
+use geodesic_path::{geodesic_disc, Mesh, SurfacePoint};
+
+fn persistent(mesh: &common::Mesh) -> Mesh {
+    Mesh::new(mesh.coordinates.clone(), mesh.indices.clone())
+}
+
+///Checks that the barycentric coordinates of `point` give back its position.
+fn assert_consistent(mesh: &common::Mesh, point: &SurfacePoint) {
+    let face = &mesh.indices[3 * point.face..3 * point.face + 3];
+    let barycentric = point.barycentric();
+    let position = point.position();
+    for (k, coordinate) in position.iter().enumerate() {
+        let expected: f32 = (0..3)
+            .map(|i| barycentric[i] * mesh.vertex(face[i])[k])
+            .sum();
+        assert!((expected - coordinate).abs() < 1e-4);
+    }
+    assert!(barycentric.iter().all(|x| *x > -1e-4));
+}
+
+///Sample points on a lattice around the unit cube.
+fn samples() -> Vec<[f32; 3]> {
+    let steps = [-1.7, -0.9, -0.3, 0.2, 0.8, 1.5];
+    let mut samples = vec![];
+    for x in steps.iter() {
+        for y in steps.iter() {
+            for z in steps.iter() {
+                samples.push([*x, *y, *z]);
+            }
+        }
+    }
+    samples
+}
+
+#[test]
+fn rays_hit_the_first_surface() {
+    let mesh = sphere(48, 24);
+    let picker = persistent(&mesh);
+    for target in samples() {
+        // From outside the sphere towards the sample, hitting the near side.
+        let origin = [target[0] * 3.0, target[1] * 3.0 + 0.1, target[2] * 3.0];
+        let direction: Vec<f32> = (0..3).map(|k| target[k] - origin[k]).collect();
+        let point = picker.pick(origin.to_vec(), direction).unwrap();
+        assert_consistent(&mesh, &point);
+        let position = point.position();
+        let radius = distance([position[0], position[1], position[2]], [0.0; 3]);
+        assert!(radius > 0.99 && radius <= 1.0 + 1e-5);
+        // No face lies between the origin and the hit.
+        let hit = distance(origin, [position[0], position[1], position[2]]);
+        let nearest = distance(origin, [0.0; 3]) - 1.0;
+        assert!(
+            hit < nearest + 0.02,
+            "hit at {} but the sphere is at {}",
+            hit,
+            nearest
+        );
+    }
+}
+
+#[test]
+fn rays_can_miss() {
+    let mesh = sphere(16, 8);
+    let picker = persistent(&mesh);
+    assert!(picker
+        .pick(vec![3.0, 0.0, 0.0], vec![0.0, 1.0, 0.0])
+        .is_none());
+    assert!(picker
+        .pick(vec![3.0, 0.0, 0.0], vec![1.0, 0.0, 0.0])
+        .is_none());
+}
+
+#[test]
+fn closest_points_match_brute_force() {
+    for mesh in [sphere(32, 16), cube(4), plane(12, 1.0)].iter() {
+        let picker = persistent(mesh);
+        for target in samples() {
+            let point = picker.closest_point(target.to_vec()).unwrap();
+            assert_consistent(mesh, &point);
+            let position = point.position();
+            let found = distance(target, [position[0], position[1], position[2]]);
+            let expected = surface_distance(mesh, target);
+            assert!((found - expected).abs() < 1e-4, "{} {}", found, expected);
+        }
+    }
+}
+
+#[test]
+fn invalid_faces_keep_the_ids_of_later_faces() {
+    let mut mesh = plane(2, 1.0);
+    // A degenerate face and one with an index out of range after the first two faces.
+    mesh.indices.splice(6..6, [0, 0, 1, 0, 1, 99]);
+    let picker = persistent(&mesh);
+    for face in (0..mesh.indices.len() / 3).filter(|face| ![2, 3].contains(face)) {
+        let corners: Vec<[f32; 3]> = mesh.indices[3 * face..3 * face + 3]
+            .iter()
+            .map(|&i| mesh.vertex(i))
+            .collect();
+        let centroid = [0, 1, 2].map(|k| corners.iter().map(|x| x[k]).sum::<f32>() / 3.0);
+        let origin = vec![centroid[0], centroid[1], 1.0];
+        let point = picker.pick(origin, vec![0.0, 0.0, -1.0]).unwrap();
+        assert_eq!(point.face, face);
+        assert_consistent(&mesh, &point);
+        let point = picker.closest_point(centroid.to_vec()).unwrap();
+        assert_eq!(point.face, face);
+
+        let disc = geodesic_disc(
+            face,
+            vec![1.0 / 3.0; 3],
+            0.01,
+            mesh.coordinates.clone(),
+            mesh.indices.clone(),
+        );
+        assert_eq!(disc.partial_faces(), vec![face]);
+    }
+    for face in [2, 3, 10] {
+        let disc = geodesic_disc(
+            face,
+            vec![1.0 / 3.0; 3],
+            1.0,
+            mesh.coordinates.clone(),
+            mesh.indices.clone(),
+        );
+        assert!(disc.vertices().is_empty());
+    }
+    // Serialized meshes keep the placeholders.
+    let copy = Mesh::from_bytes(&picker.to_bytes());
+    let point = copy.closest_point(vec![0.9, 0.6, 0.0]).unwrap();
+    assert_eq!(
+        point.face,
+        picker.closest_point(vec![0.9, 0.6, 0.0]).unwrap().face
+    );
+    // Below the diagonal of the last quad, input face 6 before the two invalid ones.
+    assert_eq!(point.face, 8);
+}
+
+#[test]
+fn short_vectors_give_nothing() {
+    let picker = persistent(&plane(2, 1.0));
+    assert!(picker.pick(vec![0.5, 0.5], vec![0.0, 0.0, -1.0]).is_none());
+    assert!(picker.pick(vec![0.5, 0.5, 1.0], vec![]).is_none());
+    assert!(picker.closest_point(vec![0.5]).is_none());
+    // Values past the third are ignored.
+    let point = picker.closest_point(vec![0.3, 0.4, 1.0, 7.0]).unwrap();
+    assert!(distance(points(&point.position())[0], [0.3, 0.4, 0.0]) < 1e-5);
+}
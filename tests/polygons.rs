@@ -0,0 +1,147 @@
+//! Meshes of quads and other polygons, triangulated on input.
+
+mod common;
+
+use common::*;
+use geodesic_path::{triangulate_polygons, Mesh as PersistentMesh, PathStatus};
+
+///Square of `size` split into `n` by `n` quads, with the vertices of `plane(n, size)`.
+fn quad_plane(n: usize, size: f32) -> (Vec<f32>, Vec<usize>, Vec<usize>) {
+    let mut indices = vec![];
+    for y in 0..n {
+        for x in 0..n {
+            let corner = y * (n + 1) + x;
+            indices.extend_from_slice(&[corner, corner + 1, corner + n + 2, corner + n + 1]);
+        }
+    }
+    (plane(n, size).coordinates, vec![4; n * n], indices)
+}
+
+fn area(coordinates: &[f32], triangle: &[usize]) -> f32 {
+    let [a, b, c] = [0, 1, 2].map(|i| &coordinates[3 * triangle[i]..3 * triangle[i] + 3]);
+    ((b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])) / 2.0
+}
+
+#[test]
+fn polygons_are_split_into_triangles() {
+    // L shaped hexagon, concave at (1, 1), and a quad with a short diagonal from (0, 0).
+    let coordinates = vec![
+        0.0, 0.0, 0.0, 2.0, 0.0, 0.0, 2.0, 1.0, 0.0, 1.0, 1.0, 0.0, 1.0, 2.0, 0.0, 0.0, 2.0, 0.0,
+        3.0, 3.0, 0.0, 3.5, 0.5, 0.0,
+    ];
+    let triangulation = triangulate_polygons(
+        coordinates.clone(),
+        vec![6, 2, 4, 3],
+        vec![0, 1, 2, 3, 4, 5, 0, 1, 1, 7, 6, 0, 0, 1, 9],
+    );
+    assert_eq!(triangulation.polygons(), vec![0, 0, 0, 0, 2, 2]);
+    let indices = triangulation.indices();
+    let areas: Vec<f32> = indices.chunks(3).map(|x| area(&coordinates, x)).collect();
+    assert!(areas.iter().all(|&area| area > 0.0));
+    assert!((areas[..4].iter().sum::<f32>() - 3.0).abs() < 1e-6);
+    let quad = &indices[12..];
+    assert!(quad.chunks(3).all(|triangle| triangle.contains(&1)));
+    assert!(quad.chunks(3).all(|triangle| triangle.contains(&6)));
+}
+
+#[test]
+fn quad_meshes_give_triangle_mesh_paths() {
+    let (coordinates, face_sizes, indices) = quad_plane(10, 1.0);
+    let mesh = PersistentMesh::from_polygons(coordinates.clone(), face_sizes, indices);
+    for &(start, end) in [(0, 120), (3, 112), (10, 110)].iter() {
+        let result = mesh.compute_path(start, end, &tight_options());
+        assert_eq!(result.status(), PathStatus::Converged);
+        let expected = distance(plane(10, 1.0).vertex(start), plane(10, 1.0).vertex(end));
+        assert!((result.length() - expected as f64).abs() < 1e-5);
+    }
+
+    let rebuilt = PersistentMesh::from_bytes(&mesh.to_bytes());
+    let point = rebuilt
+        .pick(vec![0.55, 0.35, 1.0], vec![0.0, 0.0, -1.0])
+        .unwrap();
+    assert_eq!(point.face, 35);
+    assert_eq!(point.triangle() / 2, 35);
+}
+
+#[test]
+fn face_options_refer_to_polygons() {
+    let (coordinates, face_sizes, indices) = quad_plane(10, 1.0);
+    let mesh = PersistentMesh::from_polygons(coordinates, face_sizes, indices);
+    let triangles = plane(10, 1.0);
+
+    // Quads from (0.4, 0.3) to (0.6, 0.7), blocked by polygon and by triangle.
+    let quads: Vec<usize> = (3..7).flat_map(|y| [y * 10 + 4, y * 10 + 5]).collect();
+    let mut blocked = tight_options();
+    blocked.set_blocked_faces(quads.clone());
+    let result = mesh.compute_path(5, 115, &blocked);
+    blocked.set_blocked_faces(quads.iter().flat_map(|&x| [2 * x, 2 * x + 1]).collect());
+    let expected = triangles.path_with_options(5, 115, &blocked);
+    assert!((result.length() - expected.length()).abs() < 1e-5);
+    assert!(result.length() > 1.0 + 1e-3);
+
+    // A costly band of quads across the whole plane changes nothing but the cost.
+    let costs: Vec<f32> = (0..100)
+        .map(|x| if x / 10 == 5 { 3.0 } else { 1.0 })
+        .collect();
+    mesh.set_face_costs(costs);
+    let result = mesh.compute_path(5, 115, &tight_options());
+    assert!((result.length() - 1.0).abs() < 1e-5);
+    assert!((result.cost() - 1.2).abs() < 1e-4);
+}
+
+#[test]
+fn picked_points_give_the_polygon() {
+    let (coordinates, face_sizes, indices) = quad_plane(10, 1.0);
+    let triangles = triangulate_polygons(coordinates.clone(), face_sizes.clone(), indices.clone());
+    let mesh = PersistentMesh::from_polygons(coordinates.clone(), face_sizes, indices);
+    let mut seen = vec![];
+    // Both triangles of the quad from (0.5, 0.3) to (0.6, 0.4).
+    for &[x, y] in [[0.58, 0.34], [0.52, 0.35]].iter() {
+        let point = mesh.pick(vec![x, y, 1.0], vec![0.0, 0.0, -1.0]).unwrap();
+        assert_eq!(point.face, 35);
+        assert_eq!(triangles.polygons()[point.triangle()], 35);
+        let closest = mesh.closest_point(vec![x, y, 0.1]).unwrap();
+        assert_eq!(closest.face, 35);
+        assert_eq!(closest.triangle(), point.triangle());
+
+        // Barycentric coordinates refer to the triangle.
+        let triangle = &triangles.indices()[3 * point.triangle()..3 * point.triangle() + 3];
+        let barycentric = point.barycentric();
+        let position: Vec<f32> = (0..3)
+            .map(|axis| {
+                (0..3)
+                    .map(|i| barycentric[i] * coordinates[3 * triangle[i] + axis])
+                    .sum()
+            })
+            .collect();
+        assert!(distance(points(&position)[0], [x, y, 0.0]) < 1e-5);
+        seen.push(point.triangle());
+    }
+    assert_ne!(seen[0], seen[1]);
+}
+
+#[test]
+fn distance_fields_take_points_on_triangles() {
+    // A triangle and a unit square next to it, split in two.
+    let coordinates = vec![
+        0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0, 0.0, -1.0, 0.5, 0.0,
+    ];
+    let mesh =
+        PersistentMesh::from_polygons(coordinates.clone(), vec![3, 4], vec![0, 3, 4, 0, 1, 2, 3]);
+    let point = mesh
+        .pick(vec![0.7, 0.6, 1.0], vec![0.0, 0.0, -1.0])
+        .unwrap();
+    assert_eq!(point.face, 1);
+    let field = mesh.distance_field_from_points(vec![point.triangle()], point.barycentric());
+    for vertex in 0..4 {
+        let expected = distance(points(&coordinates)[vertex], [0.7, 0.6, 0.0]);
+        assert!((field.distances()[vertex] - expected).abs() < 1e-5);
+    }
+}
+
+#[test]
+fn huge_face_sizes_end_the_polygons() {
+    let (coordinates, _, indices) = quad_plane(2, 1.0);
+    let mesh = PersistentMesh::from_polygons(coordinates, vec![4, usize::MAX, 4], indices);
+    assert_eq!(mesh.to_bytes()[4..8], 2u32.to_le_bytes());
+}
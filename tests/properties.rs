@@ -0,0 +1,127 @@
+//! Invariants of path queries on random meshes.
+
+mod common;
+
+use common::*;
+use geodesic_path::{
+    compute_path_with_options, compute_weighted_path, distance_field, distance_field_from_points,
+    distance_matrix, exp_map, geodesic_disc, get_path, get_path_mesh, log_map, trace_geodesic,
+    transport_along_path, DistanceMethod, PathOptions, PathStatus,
+};
+use proptest::prelude::*;
+
+///Grid of `n` by `n` quads with random heights and vertices jittered in the plane up to almost
+///the grid spacing, which gives needle and near degenerate triangles.
+fn terrain() -> impl Strategy<Value = Mesh> {
+    (2usize..9).prop_flat_map(|n| {
+        let count = (n + 1) * (n + 1);
+        (
+            prop::collection::vec(-0.5f32..0.5, count),
+            prop::collection::vec((-0.45f32..0.45, -0.45f32..0.45), count),
+        )
+            .prop_map(move |(heights, jitter)| {
+                let mut mesh = plane(n, n as f32);
+                for (i, (height, (x, y))) in heights.iter().zip(jitter.iter()).enumerate() {
+                    let (column, row) = (i % (n + 1), i / (n + 1));
+                    // Boundary vertices keep the square outline.
+                    if column > 0 && column < n {
+                        mesh.coordinates[3 * i] += x;
+                    }
+                    if row > 0 && row < n {
+                        mesh.coordinates[3 * i + 1] += y;
+                    }
+                    mesh.coordinates[3 * i + 2] = *height;
+                }
+                mesh
+            })
+    })
+}
+
+fn options(max_iterations: usize) -> PathOptions {
+    let mut options = PathOptions::new();
+    options.set_max_iterations(max_iterations);
+    options.set_time_limit(Some(10000.0));
+    options
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn paths_are_shorter_than_dijkstra_paths(
+        mesh in terrain(),
+        start in any::<prop::sample::Index>(),
+        end in any::<prop::sample::Index>(),
+    ) {
+        let count = mesh.coordinates.len() / 3;
+        let (start, end) = (start.index(count), end.index(count));
+        let dijkstra = mesh.path_with_options(start, end, &options(0));
+        prop_assert_eq!(dijkstra.status(), PathStatus::IterationLimit);
+        let result = mesh.path_with_options(start, end, &options(usize::MAX));
+        prop_assert_eq!(result.status(), PathStatus::Converged);
+
+        let points = points(&result.positions());
+        prop_assert!(distance(points[0], mesh.vertex(start)) < 1e-5);
+        prop_assert!(distance(points[points.len() - 1], mesh.vertex(end)) < 1e-5);
+        let (length, edge_length) = (path_length(&points), path_length(&common::points(&dijkstra.positions())));
+        prop_assert!(length <= edge_length * (1.0 + 1e-5), "{} > {}", length, edge_length);
+        for point in points.iter() {
+            let off = surface_distance(&mesh, *point);
+            prop_assert!(off < 1e-4, "point {:?} off the surface by {}", point, off);
+        }
+    }
+
+    #[test]
+    fn weighted_paths_converge(
+        mesh in terrain(),
+        costs in prop::collection::vec(prop_oneof![Just(1.0f32), 0.1f32..10.0], 128),
+        start in any::<prop::sample::Index>(),
+        end in any::<prop::sample::Index>(),
+    ) {
+        let count = mesh.coordinates.len() / 3;
+        let (start, end) = (start.index(count), end.index(count));
+        let result = compute_weighted_path(
+            start,
+            end,
+            costs,
+            &options(usize::MAX),
+            mesh.coordinates.clone(),
+            mesh.indices.clone(),
+        );
+        prop_assert_eq!(result.status(), PathStatus::Converged);
+        let points = points(&result.positions());
+        prop_assert!(distance(points[0], mesh.vertex(start)) < 1e-5);
+        prop_assert!(distance(points[points.len() - 1], mesh.vertex(end)) < 1e-5);
+        for point in points.iter() {
+            prop_assert!(surface_distance(&mesh, *point) < 1e-4);
+        }
+    }
+
+    #[test]
+    fn random_meshes_never_panic(
+        coordinates in prop::collection::vec(-10.0f32..10.0, 0..60),
+        indices in prop::collection::vec(0usize..24, 0..60),
+        start in 0usize..24,
+        end in 0usize..24,
+        vector in prop::collection::vec(-2.0f32..2.0, 0..4),
+    ) {
+        get_path(start, end, coordinates.clone(), indices.clone());
+        compute_path_with_options(start, end, &options(1000), coordinates.clone(), indices.clone());
+        get_path_mesh(start, end, coordinates.clone(), indices.clone());
+        transport_along_path(start, end, vector.clone(), coordinates.clone(), indices.clone());
+        distance_matrix(
+            vec![start, end],
+            DistanceMethod::Shortened,
+            &options(1000),
+            coordinates.clone(),
+            indices.clone(),
+        );
+        log_map(start, coordinates.clone(), indices.clone());
+        exp_map(start, vector.clone(), coordinates.clone(), indices.clone());
+        // Faces are taken from `end`, barycentric coordinates from `vector`.
+        trace_geodesic(end, vector.clone(), vector.clone(), 5.0, coordinates.clone(), indices.clone());
+        geodesic_disc(end, vector.clone(), 5.0, coordinates.clone(), indices.clone());
+        distance_field(vec![start, end], coordinates.clone(), indices.clone());
+        distance_field_from_points(vec![end], vector, coordinates, indices);
+    }
+}
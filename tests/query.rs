@@ -0,0 +1,116 @@
+//! Path queries run in steps and meshes rebuilt from their serialized bytes, as in a worker.
+
+mod common;
+
+use common::*;
+use geodesic_path::{Mesh as PersistentMesh, PathStatus};
+
+#[test]
+fn stepped_queries_match_blocking_queries() {
+    let mesh = sphere(32, 16);
+    let persistent = PersistentMesh::new(mesh.coordinates.clone(), mesh.indices.clone());
+    let expected = persistent.compute_path(3, 200, &tight_options());
+
+    let mut query = persistent.query(3, 200, &tight_options());
+    assert_eq!(query.status(), None);
+    let mut lengths = vec![query.length()];
+    while !query.step(2) {
+        lengths.push(query.length());
+    }
+    assert!(lengths.windows(2).all(|pair| pair[1] <= pair[0]));
+    assert_eq!(query.status(), Some(PathStatus::Converged));
+    assert_eq!(query.iterations(), expected.iterations());
+    assert_eq!(query.result().positions(), expected.positions());
+    // Ended queries don't step any further.
+    assert!(query.step(1));
+    assert_eq!(query.iterations(), expected.iterations());
+}
+
+#[test]
+fn unfinished_queries_give_the_path_so_far() {
+    let mesh = sphere(32, 16);
+    let persistent = PersistentMesh::new(mesh.coordinates.clone(), mesh.indices.clone());
+    let mut query = persistent.query(3, 200, &tight_options());
+    assert!(!query.step(1));
+    let result = query.result();
+    assert_eq!(result.status(), PathStatus::IterationLimit);
+    assert_eq!(result.iterations(), 1);
+    assert!(result.length() < result.dijkstra_length());
+}
+
+#[test]
+fn meshes_survive_serialization() {
+    let mesh = cube(4);
+    let persistent =
+        PersistentMesh::with_welding(mesh.coordinates.clone(), mesh.indices.clone(), 1e-4);
+    let rebuilt = PersistentMesh::from_bytes(&persistent.to_bytes());
+    for &(start, end) in [(0, 50), (10, 90)].iter() {
+        let expected = persistent.compute_path(start, end, &tight_options());
+        let result = rebuilt.compute_path(start, end, &tight_options());
+        assert_eq!(result.status(), PathStatus::Converged);
+        assert!((result.length() - expected.length()).abs() < 1e-5);
+    }
+
+    let bytes = persistent.to_bytes();
+    let truncated = PersistentMesh::from_bytes(&bytes[..bytes.len() - 1]);
+    assert_eq!(truncated.get_path(0, 50), Vec::<f32>::new());
+}
+
+#[test]
+fn costs_survive_serialization() {
+    let mesh = plane(10, 1.0);
+    let persistent = PersistentMesh::new(mesh.coordinates.clone(), mesh.indices.clone());
+    for vertex in [false, true] {
+        if vertex {
+            persistent.set_vertex_costs((0..121).map(|x| 1.0 + (x / 11) as f32).collect());
+        } else {
+            persistent.set_face_costs((0..200).map(|x| 1.0 + (x % 7) as f32).collect());
+        }
+        let expected = persistent.compute_path(0, 120, &tight_options());
+        let rebuilt = PersistentMesh::from_bytes(&persistent.to_bytes());
+        let result = rebuilt.compute_path(0, 120, &tight_options());
+        assert_eq!(result.positions(), expected.positions());
+        assert_eq!(result.cost(), expected.cost());
+        assert!(result.cost() > result.length() + 1e-3);
+    }
+}
+
+#[test]
+fn huge_counts_give_an_empty_mesh() {
+    let mut bytes = vec![];
+    for _ in 0..4 {
+        bytes.extend_from_slice(&u32::MAX.to_le_bytes());
+    }
+    bytes.extend_from_slice(&[0; 64]);
+    let mesh = PersistentMesh::from_bytes(&bytes);
+    assert!(mesh.to_bytes().iter().all(|&byte| byte == 0));
+}
+
+#[test]
+fn interleaved_queries_keep_their_own_stats_and_costs() {
+    let mesh = sphere(32, 16);
+    let persistent = PersistentMesh::new(mesh.coordinates.clone(), mesh.indices.clone());
+    let first_expected = persistent.compute_path(3, 200, &tight_options());
+    let second_expected = persistent.compute_path(0, 481, &tight_options());
+
+    let mut first = persistent.query(3, 200, &tight_options());
+    let mut second = persistent.query(0, 481, &tight_options());
+    // Costs set while the queries run apply to later queries only.
+    let costs = (0..mesh.indices.len() / 3)
+        .map(|i| 1.0 + (i % 7) as f32)
+        .collect();
+    persistent.set_face_costs(costs);
+    let (mut first_done, mut second_done) = (false, false);
+    while !(first_done && second_done) {
+        first_done = first.step(1);
+        second_done = second.step(1);
+    }
+    for (query, expected) in [(&first, &first_expected), (&second, &second_expected)] {
+        let result = query.result();
+        assert_eq!(result.positions(), expected.positions());
+        assert_eq!(result.stats().iterations, expected.stats().iterations);
+        assert_eq!(result.dijkstra_length(), expected.dijkstra_length());
+    }
+    let later = persistent.compute_path(3, 200, &tight_options());
+    assert_ne!(later.positions(), first_expected.positions());
+}
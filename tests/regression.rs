@@ -0,0 +1,116 @@
+//! Queries that used to loop forever or panic. Every query runs under a time limit, so a hang
+//! fails with `TimeLimit` instead of stalling the suite.
+
+mod common;
+
+use common::*;
+use geodesic_path::{PathOptions, PathStatus};
+
+fn options(angle_tolerance: f32) -> PathOptions {
+    let mut options = PathOptions::new();
+    options.set_angle_tolerance(angle_tolerance);
+    options.set_time_limit(Some(10000.0));
+    options
+}
+
+///Runs the query with the default and a tight angle tolerance and checks that both converge
+///to paths no longer than the first and between the right ends.
+fn assert_converges(mesh: &Mesh, start: usize, end: usize) {
+    let mut lengths = vec![];
+    for &tolerance in [PathOptions::new().angle_tolerance(), 0.001].iter() {
+        let result = mesh.path_with_options(start, end, &options(tolerance));
+        assert_eq!(result.status(), PathStatus::Converged);
+        let points = points(&result.positions());
+        assert!(distance(points[0], mesh.vertex(start)) < 1e-5);
+        assert!(distance(points[points.len() - 1], mesh.vertex(end)) < 1e-5);
+        lengths.push(path_length(&points));
+    }
+    assert!(
+        lengths[1] <= lengths[0] * (1.0 + 1e-5),
+        "lengths {:?}",
+        lengths
+    );
+}
+
+fn scaled(mut mesh: Mesh, scale: f32) -> Mesh {
+    mesh.coordinates.iter_mut().for_each(|x| *x *= scale);
+    mesh
+}
+
+#[test]
+fn same_start_and_end() {
+    let mesh = plane(4, 1.0);
+    let result = mesh.path(7, 7);
+    assert_eq!(result.status(), PathStatus::Converged);
+    assert_eq!(result.positions().len(), 3);
+}
+
+#[test]
+fn adjacent_vertices() {
+    let mesh = plane(4, 1.0);
+    let result = mesh.path(0, 6);
+    assert_eq!(result.positions().len(), 6);
+}
+
+#[test]
+fn path_along_the_boundary() {
+    let mesh = plane(10, 1.0);
+    assert_converges(&mesh, 0, 10);
+    assert_converges(&mesh, 0, 120);
+}
+
+#[test]
+fn path_around_a_reflex_boundary_corner() {
+    // L-shaped plane: the upper right quarter is cut away.
+    let n = 10;
+    let mut mesh = plane(n, 1.0);
+    mesh.indices = mesh
+        .indices
+        .chunks(3)
+        .filter(|face| {
+            let (x, y) = (face[0] % (n + 1), face[0] / (n + 1));
+            x < n / 2 || y < n / 2
+        })
+        .flatten()
+        .copied()
+        .collect();
+    let (start, end) = ((n / 2) * (n + 1) + n, n * (n + 1) + n / 2);
+    let result = mesh.path(start, end);
+    assert_eq!(result.status(), PathStatus::Converged);
+    // Straight to the corner at the centre and on to the end.
+    assert!((path_length(&points(&result.positions())) - 1.0).abs() < 1e-4);
+}
+
+#[test]
+fn zigzag_on_a_fine_grid() {
+    // A tight tolerance used to make the path zigzag between nearly collinear points.
+    let mesh = plane(20, 0.2);
+    for &end in [167, 230, 300, 440].iter() {
+        assert_converges(&mesh, 0, end);
+    }
+}
+
+#[test]
+fn long_path_on_a_small_sphere() {
+    let mesh = scaled(sphere(32, 16), 0.1);
+    assert_converges(&mesh, 3, 200);
+    assert_converges(&mesh, 0, 481);
+}
+
+#[test]
+fn path_over_cube_corners() {
+    // Corners have an angle sum of 3π/2, so the funnel meets wedges on both sides of the path.
+    let mesh = scaled(cube(8), 0.1);
+    assert_converges(&mesh, 50, 350);
+    assert_converges(&mesh, 0, 300);
+}
+
+#[test]
+fn path_on_a_large_model() {
+    // Edge weights used to overflow `i32` past about 40 model units and send Dijkstra in circles.
+    let mesh = plane(10, 1000.0);
+    let result = mesh.path(0, 120);
+    assert_eq!(result.status(), PathStatus::Converged);
+    let length = path_length(&points(&result.positions()));
+    assert!((length - 1000.0 * 2f32.sqrt()).abs() < 0.1);
+}
@@ -0,0 +1,118 @@
+//! Parallel transport of tangent vectors along computed paths.
+
+mod common;
+
+use common::*;
+use geodesic_path::{transport_along_path, ParallelTransport};
+use std::f32::consts::PI;
+
+fn transport(mesh: &Mesh, start: usize, end: usize, vector: [f32; 3]) -> ParallelTransport {
+    transport_along_path(
+        start,
+        end,
+        vector.to_vec(),
+        mesh.coordinates.clone(),
+        mesh.indices.clone(),
+    )
+}
+
+fn sub(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+///Signed angle from `ray` to `vector` in the plane of `normal`.
+fn angle(ray: [f32; 3], vector: [f32; 3], normal: [f32; 3]) -> f32 {
+    dot(cross(ray, vector), normal).atan2(dot(ray, vector))
+}
+
+fn last(values: &[f32]) -> [f32; 3] {
+    let n = values.len();
+    [values[n - 3], values[n - 2], values[n - 1]]
+}
+
+#[test]
+fn plane_transport_is_the_identity() {
+    let mesh = plane(8, 1.0);
+    let result = transport(&mesh, 0, 80, [0.3, 0.4, 0.0]);
+    assert!(result.positions().len() >= 6);
+    for tangent in points(&result.tangents()) {
+        assert!(distance(tangent, [0.3, 0.4, 0.0]) < 1e-5);
+    }
+    // Components along the normal are dropped.
+    let result = transport(&mesh, 10, 70, [0.3, 0.4, 2.0]);
+    for tangent in points(&result.tangents()) {
+        assert!(distance(tangent, [0.3, 0.4, 0.0]) < 1e-5);
+    }
+}
+
+#[test]
+fn holonomy_around_a_cone_apex_is_the_angle_defect() {
+    let (around, rings) = (12, 6);
+    let mesh = cone(around, rings, 1.0, 1.0);
+    let apex = mesh.vertex(0);
+    let sector = angle_between(sub(mesh.vertex(1), apex), sub(mesh.vertex(2), apex));
+    let defect = 2.0 * PI - around as f32 * sector;
+
+    // Geodesic triangle around the apex through three vertices of ring 4.
+    let corners = [0, 4, 8].map(|column| 1 + 3 * around + column);
+    let ray = |vertex: usize| sub(mesh.vertex(vertex), apex);
+    // Angle of the vector to the ray from the apex through the current corner. The ray is the
+    // fold line between the faces before and after the corner, so the angle carries over.
+    let (mut start_angle, mut carried) = (None, None);
+    for i in 0..3 {
+        let (from, to) = (corners[i], corners[(i + 1) % 3]);
+        let normal = transport(&mesh, from, to, ray(from)).normals();
+        let normal = [normal[0], normal[1], normal[2]];
+        let vector = match carried {
+            Some(angle) => rotated(ray(from), angle, normal),
+            None => [0.0, 1.0, 0.0],
+        };
+        let result = transport(&mesh, from, to, vector);
+        let tangents = result.tangents();
+        let initial = [tangents[0], tangents[1], tangents[2]];
+        start_angle.get_or_insert(angle(ray(from), initial, normal));
+        carried = Some(angle(ray(to), last(&tangents), last(&result.normals())));
+    }
+    let holonomy = carried.unwrap() - start_angle.unwrap();
+    let holonomy = (holonomy + 3.0 * PI).rem_euclid(2.0 * PI) - PI;
+    // The fixture faces wind clockwise seen from outside, so about their normals the
+    // counterclockwise loop turns the vector back by the defect.
+    assert!((holonomy + defect).abs() < 1e-4, "{} {}", holonomy, defect);
+}
+
+fn angle_between(a: [f32; 3], b: [f32; 3]) -> f32 {
+    (dot(a, b) / (dot(a, a) * dot(b, b)).sqrt()).acos()
+}
+
+///Rotates `ray` by `angle` about `normal` and scales it to unit length.
+fn rotated(ray: [f32; 3], angle: f32, normal: [f32; 3]) -> [f32; 3] {
+    let length = dot(ray, ray).sqrt();
+    let ray = ray.map(|x| x / length);
+    let side = cross(normal, ray);
+    [0, 1, 2].map(|k| ray[k] * angle.cos() + side[k] * angle.sin())
+}
+
+#[test]
+fn short_vectors_give_nothing() {
+    let mesh = plane(2, 1.0);
+    let result = transport_along_path(
+        0,
+        8,
+        vec![1.0, 0.0],
+        mesh.coordinates.clone(),
+        mesh.indices.clone(),
+    );
+    assert!(result.positions().is_empty());
+}
@@ -11,3 +11,47 @@ wasm_bindgen_test_configure!(run_in_browser);
 fn pass() {
     assert_eq!(1 + 1, 2);
 }
+
+///Unit square of two triangles.
+const SQUARE: [f32; 12] = [0., 0., 0., 1., 0., 0., 1., 1., 0., 0., 1., 0.];
+
+#[wasm_bindgen_test]
+fn mesh_from_typed_arrays() {
+    use geodesic_path::Mesh;
+    let mesh = Mesh::from_arrays(&SQUARE, &[0, 1, 2, 0, 2, 3]);
+    let path = mesh.compute_path(1, 3, &geodesic_path::PathOptions::new());
+    assert!((path.length() - 2f64.sqrt()).abs() < 1e-5);
+    assert_eq!(path.positions_view().to_vec(), path.positions());
+    assert_eq!(path.lengths_view().to_vec(), path.lengths());
+}
+
+#[wasm_bindgen_test]
+fn mesh_from_buffers_filled_in_place() {
+    use geodesic_path::{Mesh, MeshBuffers};
+    let mut buffers = MeshBuffers::new(4, 2);
+    buffers.coordinates().copy_from(&SQUARE);
+    buffers.indices().copy_from(&[0, 1, 2, 0, 2, 3]);
+    let mesh = Mesh::from_buffers(&buffers);
+    assert_eq!(mesh.get_path(0, 2), vec![0., 0., 0., 1., 1., 0.]);
+}
+
+///Computes the path across the unit square with `cancel` as the cancel callback.
+fn path_with_cancel(cancel: &str) -> geodesic_path::PathResult {
+    use geodesic_path::{compute_path_with_options, PathOptions};
+    let mut options = PathOptions::new();
+    options.set_cancel(Some(js_sys::Function::new_no_args(cancel)));
+    compute_path_with_options(1, 3, &options, SQUARE.to_vec(), vec![0, 1, 2, 0, 2, 3])
+}
+
+#[wasm_bindgen_test]
+fn cancel_stops_shortening() {
+    use geodesic_path::PathStatus;
+    let result = path_with_cancel("return true");
+    assert_eq!(result.status(), PathStatus::Cancelled);
+    assert_eq!(result.iterations(), 0);
+    // A throwing callback cancels too.
+    let result = path_with_cancel("throw new Error('cancelled')");
+    assert_eq!(result.status(), PathStatus::Cancelled);
+    let result = path_with_cancel("return false");
+    assert_eq!(result.status(), PathStatus::Converged);
+}
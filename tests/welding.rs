@@ -0,0 +1,102 @@
+//! Paths on meshes exported with duplicated vertices, as for flat normals or UV seams.
+
+mod common;
+
+use common::*;
+use geodesic_path::{distance_field, weld_vertices, Mesh as PersistentMesh, PathStatus};
+
+///Gives every face corner its own vertex, like a flat shaded export.
+fn flat(mesh: &Mesh) -> Mesh {
+    Mesh {
+        coordinates: mesh
+            .indices
+            .iter()
+            .flat_map(|&index| mesh.vertex(index))
+            .collect(),
+        indices: (0..mesh.indices.len()).collect(),
+    }
+}
+
+#[test]
+fn welding_keeps_input_vertices() {
+    let mesh = flat(&cube(3));
+    let welding = weld_vertices(mesh.coordinates.clone(), mesh.indices.clone(), 1e-4);
+    let vertices = welding.vertices();
+    assert_eq!(vertices.len(), mesh.coordinates.len() / 3);
+    for (vertex, &kept) in vertices.iter().enumerate() {
+        assert!(kept <= vertex && vertices[kept] == kept);
+        assert!(distance(mesh.vertex(vertex), mesh.vertex(kept)) <= 1e-4);
+    }
+    // A cube of 3 by 3 quads per side has 56 distinct vertices.
+    let mut kept = vertices.clone();
+    kept.sort_unstable();
+    kept.dedup();
+    assert_eq!(kept.len(), 56);
+    assert_eq!(welding.indices().len(), mesh.indices.len());
+}
+
+#[test]
+fn paths_cross_seams() {
+    let welded = cube(4);
+    let mesh = flat(&welded);
+    // Corners of two faces on opposite sides of the cube.
+    let (start, end) = (mesh.indices[0], mesh.indices[mesh.indices.len() - 1]);
+    let separate = PersistentMesh::new(mesh.coordinates.clone(), mesh.indices.clone());
+    assert_eq!(
+        separate.compute_path(start, end, &tight_options()).status(),
+        PathStatus::NoPath
+    );
+
+    let joined = PersistentMesh::with_welding(mesh.coordinates.clone(), mesh.indices.clone(), 1e-4);
+    let result = joined.compute_path(start, end, &tight_options());
+    assert_eq!(result.status(), PathStatus::Converged);
+    let points = points(&result.positions());
+    assert!(distance(points[0], mesh.vertex(start)) < 1e-5);
+    assert!(distance(points[points.len() - 1], mesh.vertex(end)) < 1e-5);
+
+    // The same path as on the cube built with shared vertices.
+    let find = |point: [f32; 3]| {
+        (0..welded.coordinates.len() / 3)
+            .find(|&vertex| distance(welded.vertex(vertex), point) < 1e-5)
+            .unwrap()
+    };
+    let expected = welded.path(find(mesh.vertex(start)), find(mesh.vertex(end)));
+    let expected = path_length(&common::points(&expected.positions()));
+    assert!((path_length(&points) - expected).abs() < 1e-4);
+}
+
+#[test]
+fn tolerance_merges_nearby_vertices() {
+    let mut mesh = flat(&plane(6, 1.0));
+    // Jitter every corner below the tolerance.
+    for (i, coordinate) in mesh.coordinates.iter_mut().enumerate() {
+        *coordinate += ((i * 7919) % 13) as f32 * 1e-5;
+    }
+    let joined = PersistentMesh::with_welding(mesh.coordinates.clone(), mesh.indices.clone(), 1e-3);
+    let (start, end) = (mesh.indices[0], mesh.indices[mesh.indices.len() - 2]);
+    let result = joined.compute_path(start, end, &tight_options());
+    assert_eq!(result.status(), PathStatus::Converged);
+    let length = path_length(&points(&result.positions()));
+    assert!((length - distance(mesh.vertex(start), mesh.vertex(end))).abs() < 1e-3);
+}
+
+#[test]
+fn distance_fields_cover_every_input_vertex() {
+    let welded = plane(4, 1.0);
+    let mesh = flat(&welded);
+    let joined = PersistentMesh::with_welding(mesh.coordinates.clone(), mesh.indices.clone(), 1e-4);
+    let field = joined.distance_field(vec![mesh.indices[0]]);
+    let expected = distance_field(
+        vec![welded.indices[0]],
+        welded.coordinates.clone(),
+        welded.indices.clone(),
+    );
+    // Every face corner is a vertex of its own, at the same place as its vertex in `welded`.
+    let count = mesh.coordinates.len() / 3;
+    assert_eq!(field.distances().len(), count);
+    for corner in 0..count {
+        let vertex = welded.indices[corner];
+        assert!((field.distances()[corner] - expected.distances()[vertex]).abs() < 1e-5);
+        assert_eq!(field.sources()[corner], 0);
+    }
+}
@@ -41,39 +41,36 @@ const pathMaterial = new LineMaterial({
 });
 pathMaterial.resolution.set(window.innerWidth, window.innerHeight);
 
-const makeModel = (geometry: THREE.BufferGeometry) => {
-  const coordinates = geometry.getAttribute("position").array as Float32Array
-
-  // let pathPoints = wasm.get_path(3000, 2000, coordinates,
-  //   geometry.index!.array as Uint32Array);
-  let pathPoints = wasm.get_path(4000, 25215, coordinates,
-    geometry.index!.array as Uint32Array);
-  console.log(pathPoints, geometry.index!.array)
-  geometry.computeVertexNormals();
-
-  let maxZ = 0;
-  let index = 0
-  for (let i = 1; i < coordinates.length; i += 3) {
-    if (coordinates[i] > maxZ) {
-      maxZ = coordinates[i];
-      index = (i - 1) / 3
-    }
-  }
-
-  console.error(index);
-
-
+const makePath = (pathPoints: Float32Array) => {
   const lineGeometry = new THREE.BufferGeometry().setAttribute("position", new THREE.Float32BufferAttribute(pathPoints, 3));
-  // const path = new THREE.Line(lineGeometry, new THREE.LineBasicMaterial({ color: "red" }));
-  // path.renderOrder = 2
   const pathGeometry = new LineGeometry().fromLine(new THREE.Line(lineGeometry));
 
   const path = new Line2(pathGeometry, pathMaterial);
   path.renderOrder = 2
+  return path;
+}
+
+const makeModel = (geometry: THREE.BufferGeometry) => {
+  const coordinates = geometry.getAttribute("position").array as Float32Array
+  const mesh = wasm.Mesh.from_arrays(coordinates, geometry.index!.array as Uint32Array);
+
+  // The query yields to the event loop, so the scene keeps rendering while it runs.
+  const options = new wasm.PathOptions();
+  options.progress = (iterations: number, length: number) => {
+    if (iterations % 100 === 0) {
+      console.log(`iteration ${iterations}, length ${length}`);
+    }
+  };
+  mesh.compute_path_async(4000, 25215, options).then((result: wasm.PathResult) => {
+    console.log(`path length ${result.length} in ${result.iterations} iterations`);
+    scene.add(makePath(result.positions));
+    result.free();
+    mesh.free();
+  });
+  geometry.computeVertexNormals();
 
   const material = new THREE.MeshStandardMaterial({ color: 0x606060, vertexColors: false, wireframe: false });
-  const model = new THREE.Mesh(geometry, material);
-  return [path, model];
+  return new THREE.Mesh(geometry, material);
 }
 
 const showBunny = () => {
@@ -82,7 +79,7 @@ const showBunny = () => {
     camera.position.set(0.1, 0.2, 0.5);
     camera.lookAt(0, 0.1, 0);
     controls.target.set(0, 0.1, 0);
-    scene.add(...makeModel(bunny));
+    scene.add(makeModel(bunny));
     const spot = new THREE.DirectionalLight();
     spot.position.set(1, 1, 1);
 